@@ -1,16 +1,4103 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { createHash } from "crypto";
 import { IncarraContract } from "../target/types/incarra_contract";
 
+const { Keypair, LAMPORTS_PER_SOL, PublicKey } = anchor.web3;
+
 describe("incarra-contract", () => {
   // Configure the client to use the local cluster.
-  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
 
   const program = anchor.workspace.incarraContract as Program<IncarraContract>;
 
-  it("Is initialized!", async () => {
-    // Add your test here.
-    const tx = await program.methods.initialize().rpc();
-    console.log("Your transaction signature", tx);
+  const CARV_ID = "0x" + "ab".repeat(20);
+  // 32 bytes of hex, as verify_carv_id requires
+  const VERIFICATION_PROOF = "0x" + "0123456789abcdef".repeat(4);
+
+  const admin = provider.wallet.publicKey;
+  const configPda = PublicKey.findProgramAddressSync(
+    [Buffer.from("config")],
+    program.programId
+  )[0];
+  const programDataPda = PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    anchor.web3.BPF_LOADER_UPGRADEABLE_PROGRAM_ID
+  )[0];
+
+  // Every config field, so partial updates can leave the rest unchanged
+  const NO_CONFIG_CHANGES = {
+    admin: null,
+    interactionCooldownSeconds: null,
+    verifiedCooldownSeconds: null,
+    rateLimitWindowSeconds: null,
+    maxInteractionsPerWindow: null,
+    verificationBonus: null,
+    minInteractionsForCredentials: null,
+    disputeReputationThreshold: null,
+    warmupSeconds: null,
+    decayPeriodSeconds: null,
+    decayPercentPerPeriod: null,
+    rareAchievementScore: null,
+    epicAchievementScore: null,
+    legendaryAchievementScore: null,
+    maxAchievementsPerIssuer: null,
+    dailyXpCap: null,
+    unverifiedCredentialThreshold: null,
+    unverifiedCredentialPenalty: null,
+    dataSourceReputation: null,
+    dataSourceDiversityBonus: null,
+    verificationValiditySeconds: null,
+    penaltyCooldownSeconds: null,
+    researchProjectReputation: null,
+    minLevelForAchievements: null,
+    verifiedAchievementMultiplierBps: null,
+    decayExemptVerifiedCredentials: null,
+    renameCooldownSeconds: null,
+    explorerBonus: null,
+    contextBonusEnabled: null,
+    maxContextBonus: null,
+    dormancyThresholdSeconds: null,
+    maxAttestationsPerChain: null,
+    uniqueHandles: null,
+    referralReputation: null,
+    cosignThreshold: null,
+    minCreationStake: null,
+    verifiedImportGrace: null,
+    decayCurve: null,
+    reputationFloor: null,
+    credentialBonuses: null,
+  };
+
+  type ConfigFields = Partial<Record<keyof typeof NO_CONFIG_CHANGES, any>>;
+
+  const updateConfig = (fields: ConfigFields) =>
+    program.methods
+      .updateConfig({ ...NO_CONFIG_CHANGES, ...fields })
+      .accountsPartial({ config: configPda, admin })
+      .rpc();
+
+  before(async () => {
+    if (!(await provider.connection.getAccountInfo(configPda))) {
+      await program.methods
+        .initializeConfig()
+        .accountsPartial({ config: configPda, admin, programData: programDataPda })
+        .rpc();
+    }
+    await updateConfig({
+      interactionCooldownSeconds: new anchor.BN(0),
+      maxInteractionsPerWindow: new anchor.BN(0),
+      verificationBonus: new anchor.BN(50),
+      minInteractionsForCredentials: new anchor.BN(10),
+    });
+  });
+
+  const agentPda = (owner: anchor.web3.PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("incarra_agent"), owner.toBuffer()],
+      program.programId
+    )[0];
+
+  const carvIndexPda = (carvId: string) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("carv_index"), createHash("sha256").update(carvId).digest()],
+      program.programId
+    )[0];
+
+  const airdrop = async (to: anchor.web3.PublicKey) => {
+    const signature = await provider.connection.requestAirdrop(
+      to,
+      2 * LAMPORTS_PER_SOL
+    );
+    const latest = await provider.connection.getLatestBlockhash();
+    await provider.connection.confirmTransaction({ signature, ...latest });
+  };
+
+  // Funds a fresh owner and creates its agent PDA
+  const createAgent = async (name = "TestBot", signature = "0xsignature") => {
+    const owner = Keypair.generate();
+    await airdrop(owner.publicKey);
+    const agent = agentPda(owner.publicKey);
+
+    await program.methods
+      .createIncarraAgent(name, "Helpful AI assistant", CARV_ID, signature)
+      .accountsPartial({ incarraAgent: agent, user: owner.publicKey })
+      .signers([owner])
+      .rpc();
+
+    return { owner, agent };
+  };
+
+  // Signed by the owner or an authorized recorder
+  const interact = (
+    signer: anchor.web3.Keypair,
+    agent: anchor.web3.PublicKey,
+    experience = 10,
+    interactionType: any = { conversation: {} },
+    requiredArea: string | null = null
+  ) =>
+    program.methods
+      .interactWithIncarra(
+        interactionType,
+        new anchor.BN(experience),
+        "{}",
+        requiredArea
+      )
+      .accountsPartial({
+        incarraAgent: agent,
+        recorder: signer.publicKey,
+        config: configPda,
+      })
+      .signers([signer])
+      .rpc();
+
+  const verify = (
+    owner: anchor.web3.Keypair,
+    agent: anchor.web3.PublicKey,
+    proof = VERIFICATION_PROOF
+  ) =>
+    program.methods
+      .verifyCarvId(proof)
+      .accountsPartial({
+        incarraAgent: agent,
+        owner: owner.publicKey,
+        config: configPda,
+      })
+      .signers([owner])
+      .rpc({ commitment: "confirmed" });
+
+  const addKnowledgeArea = (
+    owner: anchor.web3.Keypair,
+    agent: anchor.web3.PublicKey,
+    area: string
+  ) =>
+    program.methods
+      .addKnowledgeArea(area)
+      .accountsPartial({ incarraAgent: agent, owner: owner.publicKey })
+      .signers([owner])
+      .rpc();
+
+  const addAchievement = (
+    owner: anchor.web3.Keypair,
+    agent: anchor.web3.PublicKey,
+    name: string,
+    score: number | anchor.BN,
+    expiresAt: number | null = null
+  ) =>
+    program.methods
+      .addAchievement(
+        name,
+        `${name} description`,
+        new anchor.BN(score),
+        expiresAt === null ? null : new anchor.BN(expiresAt)
+      )
+      .accountsPartial({ incarraAgent: agent, owner: owner.publicKey })
+      .signers([owner])
+      .rpc({ commitment: "confirmed" });
+
+  const addCredential = (
+    owner: anchor.web3.Keypair,
+    agent: anchor.web3.PublicKey,
+    credentialType = "Skill",
+    credentialData = "{}",
+    issuer = "Incarra Academy",
+    prerequisiteIndex: number | null = null
+  ) =>
+    program.methods
+      .addCredential(
+        credentialType,
+        credentialData,
+        issuer,
+        prerequisiteIndex === null ? null : new anchor.BN(prerequisiteIndex)
+      )
+      .accountsPartial({
+        incarraAgent: agent,
+        owner: owner.publicKey,
+        config: configPda,
+      })
+      .signers([owner])
+      .rpc();
+
+  const addVerifiedCredential = (
+    owner: anchor.web3.Keypair,
+    agent: anchor.web3.PublicKey,
+    issuer: anchor.web3.Keypair,
+    credentialType = "Certification"
+  ) =>
+    program.methods
+      .addVerifiedCredential(credentialType, "{}", "Signed Issuer")
+      .accountsPartial({
+        incarraAgent: agent,
+        owner: owner.publicKey,
+        issuer: issuer.publicKey,
+        config: configPda,
+      })
+      .signers([owner, issuer])
+      .rpc();
+
+  // Decodes the program events emitted by a confirmed transaction
+  const eventsOf = async (signature: string) => {
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(program.programId, program.coder);
+    return Array.from(parser.parseLogs(tx.meta.logMessages));
+  };
+
+  // Decodes the value an instruction returned via `set_return_data`
+  const returnOf = async (signature: string, typeName: string) => {
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const prefix = `Program return: ${program.programId.toBase58()} `;
+    const log = tx.meta.logMessages.find((line) => line.startsWith(prefix));
+    const data = Buffer.from(log.slice(prefix.length), "base64");
+    return program.coder.types.decode(typeName, data);
+  };
+
+  const hasEvent = (events: anchor.Event[], name: string) =>
+    events.some((event) => event.name.toLowerCase() === name.toLowerCase());
+
+  const expectError = async (promise: Promise<unknown>, code: string) => {
+    try {
+      await promise;
+      expect.fail(`expected ${code}`);
+    } catch (err) {
+      expect(err).to.be.instanceOf(anchor.AnchorError);
+      expect((err as anchor.AnchorError).error.errorCode.code).to.equal(code);
+    }
+  };
+
+  // View failures surface either as an AnchorError or a raw simulation error
+  const expectViewError = async (promise: Promise<unknown>, code: string) => {
+    try {
+      await promise;
+      expect.fail(`expected ${code}`);
+    } catch (err) {
+      const logs = err.logs ?? err.simulationResponse?.logs ?? [];
+      const text = [String(err), err.error?.errorCode?.code, ...logs].join("\n");
+      expect(text).to.contain(code);
+    }
+  };
+
+  it("Creates an agent", async () => {
+    const { owner, agent } = await createAgent();
+
+    const account = await program.account.incarraAgent.fetch(agent);
+    expect(account.owner.toBase58()).to.equal(owner.publicKey.toBase58());
+    expect(account.level.toNumber()).to.equal(1);
+    expect(account.isActive).to.equal(true);
+  });
+
+  describe("suggest_achievements", () => {
+    it("suggests every standard milestone for a new agent", async () => {
+      const { agent } = await createAgent();
+
+      const suggestions = await program.methods
+        .suggestAchievements()
+        .accountsPartial({ incarraAgent: agent })
+        .view();
+
+      expect(suggestions).to.deep.equal([
+        "First Interaction",
+        "First Credential",
+        "Verified Identity",
+        "Knowledge Explorer",
+        "Level 5",
+        "Level 10",
+        "Century Club",
+      ]);
+    });
+
+    it("drops milestones a seasoned agent has already reached", async () => {
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+      await interact(owner, agent, 450);
+      for (const area of ["math", "physics", "biology", "history", "music"]) {
+        await addKnowledgeArea(owner, agent, area);
+      }
+
+      const suggestions = await program.methods
+        .suggestAchievements()
+        .accountsPartial({ incarraAgent: agent })
+        .view();
+
+      expect(suggestions).to.deep.equal([
+        "First Credential",
+        "Level 10",
+        "Century Club",
+      ]);
+    });
+  });
+
+  describe("custom interactions", () => {
+    it("rejects a custom label longer than 20 characters", async () => {
+      const { owner, agent } = await createAgent();
+
+      await expectError(
+        interact(owner, agent, 10, { custom: ["x".repeat(21)] }),
+        "CustomLabelTooLong"
+      );
+    });
+
+    it("counts a valid custom interaction", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent, 10, { custom: ["code-review"] });
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.customInteractions.toNumber()).to.equal(1);
+      expect(account.totalInteractions.toNumber()).to.equal(1);
+      expect(account.reputationScore.toNumber()).to.equal(2);
+    });
+  });
+
+  describe("check_qualification", () => {
+    const qualifies = (agent: anchor.web3.PublicKey, role: any) =>
+      program.methods
+        .checkQualification(role)
+        .accountsPartial({ incarraAgent: agent, config: configPda })
+        .view();
+
+    const researchQuery = { researchQuery: {} };
+
+    it("evaluates Mentor at, below, and above level 10", async () => {
+      const atLevel = await createAgent();
+      await verify(atLevel.owner, atLevel.agent);
+      await interact(atLevel.owner, atLevel.agent, 900);
+      expect(await qualifies(atLevel.agent, { mentor: {} })).to.equal(true);
+
+      const belowLevel = await createAgent();
+      await verify(belowLevel.owner, belowLevel.agent);
+      await interact(belowLevel.owner, belowLevel.agent, 899);
+      expect(await qualifies(belowLevel.agent, { mentor: {} })).to.equal(false);
+
+      const unverified = await createAgent();
+      await interact(unverified.owner, unverified.agent, 900);
+      expect(await qualifies(unverified.agent, { mentor: {} })).to.equal(false);
+
+      const veteran = await createAgent();
+      await verify(veteran.owner, veteran.agent);
+      await interact(veteran.owner, veteran.agent, 5000);
+      expect(await qualifies(veteran.agent, { mentor: {} })).to.equal(true);
+    });
+
+    it("evaluates Researcher at, below, and above its thresholds", async () => {
+      const setup = async (projects: number, areas: number) => {
+        const { owner, agent } = await createAgent();
+        for (let i = 0; i < projects; i++) {
+          await interact(owner, agent, 1, researchQuery);
+        }
+        for (let i = 0; i < areas; i++) {
+          await addKnowledgeArea(owner, agent, `area-${i}`);
+        }
+        return agent;
+      };
+
+      expect(await qualifies(await setup(5, 3), { researcher: {} })).to.equal(
+        true
+      );
+      expect(await qualifies(await setup(4, 3), { researcher: {} })).to.equal(
+        false
+      );
+      expect(await qualifies(await setup(5, 2), { researcher: {} })).to.equal(
+        false
+      );
+      expect(await qualifies(await setup(8, 6), { researcher: {} })).to.equal(
+        true
+      );
+    });
+
+    it("evaluates Verified before and after verification", async () => {
+      const { owner, agent } = await createAgent();
+      expect(await qualifies(agent, { verified: {} })).to.equal(false);
+
+      await verify(owner, agent);
+      expect(await qualifies(agent, { verified: {} })).to.equal(true);
+
+      await interact(owner, agent, 1000);
+      expect(await qualifies(agent, { verified: {} })).to.equal(true);
+    });
+  });
+
+  describe("reputation cap", () => {
+    const MAX_REPUTATION_SCORE = 1_000_000;
+
+    it("saturates reputation_score and reports the cap exactly once", async () => {
+      const { owner, agent } = await createAgent();
+
+      const below = await addAchievement(owner, agent, "Almost", 999_990);
+      expect(hasEvent(await eventsOf(below), "ReputationCapReached")).to.equal(
+        false
+      );
+
+      const reaching = await addAchievement(owner, agent, "Over", 100);
+      expect(hasEvent(await eventsOf(reaching), "ReputationCapReached")).to.equal(
+        true
+      );
+
+      let account = await program.account.incarraAgent.fetch(agent);
+      expect(account.reputationScore.toNumber()).to.equal(MAX_REPUTATION_SCORE);
+
+      const beyond = await addAchievement(owner, agent, "Beyond", 500);
+      expect(hasEvent(await eventsOf(beyond), "ReputationCapReached")).to.equal(
+        false
+      );
+      await interact(owner, agent, 10);
+
+      account = await program.account.incarraAgent.fetch(agent);
+      expect(account.reputationScore.toNumber()).to.equal(MAX_REPUTATION_SCORE);
+    });
+  });
+
+  describe("recompute_reputation", () => {
+    it("keeps the canonical score after a repeated verification attempt", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent, 10, { dataAnalysis: {} });
+      await addKnowledgeArea(owner, agent, "chemistry");
+      await addAchievement(owner, agent, "Pioneer", 25);
+      await verify(owner, agent);
+      await expectError(verify(owner, agent), "AlreadyVerified");
+
+      // 5 (interaction) + 2 (area) + 25 (achievement) + 50 (verification)
+      const canonical = 82;
+      let account = await program.account.incarraAgent.fetch(agent);
+      expect(account.reputationScore.toNumber()).to.equal(canonical);
+
+      await program.methods
+        .recomputeReputation()
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+
+      account = await program.account.incarraAgent.fetch(agent);
+      expect(account.reputationScore.toNumber()).to.equal(canonical);
+    });
+  });
+
+  describe("required knowledge area", () => {
+    it("rejects an interaction until the required area is added", async () => {
+      const { owner, agent } = await createAgent();
+
+      await expectError(
+        interact(owner, agent, 10, { researchQuery: {} }, "genomics"),
+        "MissingKnowledgeArea"
+      );
+
+      await addKnowledgeArea(owner, agent, "genomics");
+      await interact(owner, agent, 10, { researchQuery: {} }, "genomics");
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.researchProjects.toNumber()).to.equal(1);
+    });
+  });
+
+  describe("get_account_size", () => {
+    it("matches the space allocated for a new agent", async () => {
+      const { agent } = await createAgent();
+
+      const size = await program.methods
+        .getAccountSize()
+        .accountsPartial({ incarraAgent: agent })
+        .view();
+      const info = await provider.connection.getAccountInfo(agent);
+
+      expect(info.data.length).to.equal(size.toNumber());
+    });
+  });
+
+  describe("issuer-signed credentials", () => {
+    it("records the issuer pubkey and filters credentials by it", async () => {
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+      const issuerA = Keypair.generate();
+      const issuerB = Keypair.generate();
+
+      await addVerifiedCredential(owner, agent, issuerA);
+      await addVerifiedCredential(owner, agent, issuerB);
+      await addVerifiedCredential(owner, agent, issuerA, "Education");
+      await addCredential(owner, agent);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.credentials[0].issuerPubkey.toBase58()).to.equal(
+        issuerA.publicKey.toBase58()
+      );
+      expect(account.credentials[0].isVerified).to.equal(true);
+      expect(account.credentials[3].issuerPubkey).to.equal(null);
+
+      const fromA = await program.methods
+        .getCredentialsByIssuerPubkey(issuerA.publicKey)
+        .accountsPartial({ incarraAgent: agent })
+        .view();
+      expect(fromA.map((c) => c.credentialType)).to.deep.equal([
+        "Certification",
+        "Education",
+      ]);
+    });
+  });
+
+  describe("admin_seed_interaction", () => {
+    const adminSeed = (
+      agent: anchor.web3.PublicKey,
+      signer?: anchor.web3.Keypair
+    ) =>
+      program.methods
+        .adminSeedInteraction({ researchQuery: {} }, new anchor.BN(20))
+        .accountsPartial({
+          incarraAgent: agent,
+          config: configPda,
+          admin: signer ? signer.publicKey : admin,
+        })
+        .signers(signer ? [signer] : [])
+        .rpc();
+
+    before(() =>
+      updateConfig({ interactionCooldownSeconds: new anchor.BN(3600) })
+    );
+    after(() => updateConfig({ interactionCooldownSeconds: new anchor.BN(0) }));
+
+    it("lets the admin record interactions inside the cooldown", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent);
+      await expectError(interact(owner, agent), "InteractionCooldownActive");
+
+      await adminSeed(agent);
+      await adminSeed(agent);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.totalInteractions.toNumber()).to.equal(3);
+      expect(account.researchProjects.toNumber()).to.equal(2);
+      expect(account.experience.toNumber()).to.equal(50);
+    });
+
+    it("rejects a non-admin caller", async () => {
+      const { owner, agent } = await createAgent();
+
+      await expectError(adminSeed(agent, owner), "Unauthorized");
+    });
+  });
+
+  describe("verify_carv_id result", () => {
+    it("returns the verification outcome and bonus applied", async () => {
+      const { owner, agent } = await createAgent();
+      const before = await program.account.incarraAgent.fetch(agent);
+
+      const signature = await verify(owner, agent);
+      const result = await returnOf(signature, "verificationResult");
+
+      const after = await program.account.incarraAgent.fetch(agent);
+      expect(result.verified).to.equal(true);
+      expect(after.carvVerified).to.equal(true);
+      expect(result.method).to.deep.equal({ ownershipProof: {} });
+      expect(result.reputationGained.toNumber()).to.equal(50);
+      expect(
+        after.reputationScore.sub(before.reputationScore).toNumber()
+      ).to.equal(result.reputationGained.toNumber());
+    });
+  });
+
+  describe("add_achievements", () => {
+    const achievement = (name: string, score: number) => ({
+      name,
+      description: `${name} description`,
+      score: new anchor.BN(score),
+      expiresAt: null,
+    });
+
+    const addBatch = (
+      owner: anchor.web3.Keypair,
+      agent: anchor.web3.PublicKey,
+      batch: ReturnType<typeof achievement>[]
+    ) =>
+      program.methods
+        .addAchievements(batch)
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+
+    it("adds a batch and sums its scores into reputation", async () => {
+      const { owner, agent } = await createAgent();
+
+      await addBatch(owner, agent, [
+        achievement("Gold", 30),
+        achievement("Silver", 20),
+        achievement("Bronze", 10),
+      ]);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.achievements.map((a) => a.name)).to.deep.equal([
+        "Gold",
+        "Silver",
+        "Bronze",
+      ]);
+      expect(account.achievements[0].earnedAt.toNumber()).to.be.greaterThan(0);
+      expect(account.reputationScore.toNumber()).to.equal(60);
+    });
+
+    it("rejects a batch that would overflow the cap without applying it", async () => {
+      const { owner, agent } = await createAgent();
+      for (let i = 0; i < 19; i++) {
+        await addAchievement(owner, agent, `Round ${i}`, 1);
+      }
+
+      await expectError(
+        addBatch(owner, agent, [achievement("Final", 5), achievement("Bonus", 5)]),
+        "TooManyAchievements"
+      );
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.achievements.length).to.equal(19);
+      expect(account.reputationScore.toNumber()).to.equal(19);
+    });
+
+    it("skips duplicate names within the batch and against existing ones", async () => {
+      const { owner, agent } = await createAgent();
+      await addAchievement(owner, agent, "Finalist", 10);
+
+      await addBatch(owner, agent, [
+        achievement("Winner", 50),
+        achievement("Winner", 50),
+        achievement("Finalist", 10),
+      ]);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.achievements.map((a) => a.name)).to.deep.equal([
+        "Finalist",
+        "Winner",
+      ]);
+      expect(account.reputationScore.toNumber()).to.equal(60);
+    });
+  });
+
+  describe("reset_rate_limits", () => {
+    const resetRateLimits = (
+      agent: anchor.web3.PublicKey,
+      signer?: anchor.web3.Keypair
+    ) =>
+      program.methods
+        .resetRateLimits()
+        .accountsPartial({
+          incarraAgent: agent,
+          config: configPda,
+          admin: signer ? signer.publicKey : admin,
+        })
+        .signers(signer ? [signer] : [])
+        .rpc();
+
+    before(() =>
+      updateConfig({
+        rateLimitWindowSeconds: new anchor.BN(3600),
+        maxInteractionsPerWindow: new anchor.BN(2),
+      })
+    );
+    after(() => updateConfig({ maxInteractionsPerWindow: new anchor.BN(0) }));
+
+    it("lets an agent at its cap interact again after an admin reset", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent);
+      await interact(owner, agent);
+      await expectError(interact(owner, agent), "RateLimitExceeded");
+
+      await resetRateLimits(agent);
+      await interact(owner, agent);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.totalInteractions.toNumber()).to.equal(3);
+    });
+
+    it("rejects a reset from a non-admin", async () => {
+      const { owner, agent } = await createAgent();
+
+      await expectError(resetRateLimits(agent, owner), "Unauthorized");
+    });
+
+    it("rejects a rate limit without a positive window", async () => {
+      await expectError(
+        updateConfig({ rateLimitWindowSeconds: new anchor.BN(0) }),
+        "InvalidRateLimitWindow"
+      );
+      await expectError(
+        updateConfig({
+          rateLimitWindowSeconds: new anchor.BN(-1),
+          maxInteractionsPerWindow: new anchor.BN(5),
+        }),
+        "InvalidRateLimitWindow"
+      );
+
+      const config = await program.account.config.fetch(configPda);
+      expect(config.rateLimitWindowSeconds.toNumber()).to.equal(3600);
+    });
+  });
+
+  describe("knowledge area ordering", () => {
+    it("keeps knowledge areas sorted and ignores duplicates", async () => {
+      const { owner, agent } = await createAgent();
+      for (const area of ["zoology", "art", "math", "biology", "art"]) {
+        await addKnowledgeArea(owner, agent, area);
+      }
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.knowledgeAreas).to.deep.equal([
+        "art",
+        "biology",
+        "math",
+        "zoology",
+      ]);
+      expect(account.reputationScore.toNumber()).to.equal(8);
+    });
+  });
+
+  describe("attest_profile", () => {
+    it("writes a program-owned snapshot of the agent's reputation", async () => {
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+      await interact(owner, agent, 150);
+      const attestation = Keypair.generate();
+
+      await program.methods
+        .attestProfile()
+        .accountsPartial({
+          incarraAgent: agent,
+          attestation: attestation.publicKey,
+          owner: owner.publicKey,
+        })
+        .signers([owner, attestation])
+        .rpc();
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      const record = await program.account.profileAttestation.fetch(
+        attestation.publicKey
+      );
+      expect(record.agent.toBase58()).to.equal(agent.toBase58());
+      expect(record.owner.toBase58()).to.equal(owner.publicKey.toBase58());
+      expect(record.reputationScore.toNumber()).to.equal(
+        account.reputationScore.toNumber()
+      );
+      expect(record.level.toNumber()).to.equal(2);
+      expect(record.carvVerified).to.equal(true);
+
+      const info = await provider.connection.getAccountInfo(
+        attestation.publicKey
+      );
+      expect(info.owner.toBase58()).to.equal(program.programId.toBase58());
+    });
+  });
+
+  describe("configurable verification bonus", () => {
+    after(() => updateConfig({ verificationBonus: new anchor.BN(50) }));
+
+    it("grants the configured bonus and remembers it per agent", async () => {
+      await updateConfig({ verificationBonus: new anchor.BN(80) });
+      const { owner, agent } = await createAgent();
+
+      const result = await returnOf(
+        await verify(owner, agent),
+        "verificationResult"
+      );
+      expect(result.reputationGained.toNumber()).to.equal(80);
+
+      // A later config change doesn't alter what this agent was granted
+      await updateConfig({ verificationBonus: new anchor.BN(10) });
+      await program.methods
+        .recomputeReputation()
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.verificationBonusGranted.toNumber()).to.equal(80);
+      expect(account.reputationScore.toNumber()).to.equal(80);
+
+      const other = await createAgent();
+      const otherResult = await returnOf(
+        await verify(other.owner, other.agent),
+        "verificationResult"
+      );
+      expect(otherResult.reputationGained.toNumber()).to.equal(10);
+    });
+  });
+
+  describe("get_credentials_summary", () => {
+    const summaryOf = (agent: anchor.web3.PublicKey) =>
+      program.methods
+        .getCredentialsSummary()
+        .accountsPartial({ incarraAgent: agent })
+        .view();
+
+    it("groups credentials by type case-insensitively", async () => {
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+      for (const type of ["Skill", "Education", "skill", "SKILL", "Education"]) {
+        await addCredential(owner, agent, type);
+      }
+      await addCredential(owner, agent, "Certification");
+
+      const summary = await summaryOf(agent);
+      expect(
+        summary.map((entry) => [entry.credentialType, entry.count.toNumber()])
+      ).to.deep.equal([
+        ["Skill", 3],
+        ["Education", 2],
+        ["Certification", 1],
+      ]);
+    });
+
+    it("returns an empty summary when there are no credentials", async () => {
+      const { agent } = await createAgent();
+
+      expect(await summaryOf(agent)).to.deep.equal([]);
+    });
+  });
+
+  describe("credential activity requirement", () => {
+    before(() =>
+      updateConfig({ minInteractionsForCredentials: new anchor.BN(3) })
+    );
+    after(() =>
+      updateConfig({ minInteractionsForCredentials: new anchor.BN(10) })
+    );
+
+    it("blocks an inactive unverified agent until it has enough interactions", async () => {
+      const { owner, agent } = await createAgent();
+      await expectError(addCredential(owner, agent), "InsufficientActivity");
+
+      for (let i = 0; i < 3; i++) {
+        await interact(owner, agent);
+      }
+      await addCredential(owner, agent);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.credentials.length).to.equal(1);
+    });
+
+    it("exempts verified agents", async () => {
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+
+      await addCredential(owner, agent);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.credentials.length).to.equal(1);
+    });
+  });
+
+  describe("get_full_profile", () => {
+    it("bundles the same data the individual reads return", async () => {
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+      await interact(owner, agent, 130);
+      await addKnowledgeArea(owner, agent, "robotics");
+      await addCredential(owner, agent);
+      await addAchievement(owner, agent, "Builder", 15);
+
+      const read = (method: string) =>
+        program.methods[method]()
+          .accountsPartial({ incarraAgent: agent })
+          .view();
+      const full = await read("getFullProfile");
+
+      expect(JSON.stringify(full.context)).to.equal(
+        JSON.stringify(await read("getIncarraContext"))
+      );
+      expect(JSON.stringify(full.carvProfile)).to.equal(
+        JSON.stringify(await read("getCarvProfile"))
+      );
+      expect(full.storage.credentialsUsed.toNumber()).to.equal(1);
+      expect(full.storage.credentialsMax.toNumber()).to.equal(10);
+      expect(full.storage.achievementsUsed.toNumber()).to.equal(1);
+      expect(full.storage.knowledgeAreasUsed.toNumber()).to.equal(1);
+      expect(full.levelProgress.level.toNumber()).to.equal(2);
+      expect(full.levelProgress.experienceIntoLevel.toNumber()).to.equal(30);
+      expect(full.levelProgress.experienceToNextLevel.toNumber()).to.equal(70);
+    });
+  });
+
+  describe("get_incarra_context_for", () => {
+    it("returns the context when the expected owner matches", async () => {
+      const { owner, agent } = await createAgent("OwnerCheck");
+
+      const context = await program.methods
+        .getIncarraContextFor(owner.publicKey)
+        .accountsPartial({ incarraAgent: agent })
+        .view();
+
+      expect(context.owner.toBase58()).to.equal(owner.publicKey.toBase58());
+      expect(context.agentName).to.equal("OwnerCheck");
+    });
+
+    it("rejects an agent account that doesn't belong to the expected owner", async () => {
+      const { agent } = await createAgent();
+      const stranger = Keypair.generate();
+
+      await expectViewError(
+        program.methods
+          .getIncarraContextFor(stranger.publicKey)
+          .accountsPartial({ incarraAgent: agent })
+          .view(),
+        "ConstraintSeeds"
+      );
+    });
+  });
+
+  describe("knowledge area cap", () => {
+    const MAX_KNOWLEDGE_AREAS = 20;
+
+    it("accepts the last area at the cap and rejects the next one", async () => {
+      const { owner, agent } = await createAgent();
+      for (let i = 0; i < MAX_KNOWLEDGE_AREAS; i++) {
+        await addKnowledgeArea(owner, agent, `area-${String(i).padStart(2, "0")}`);
+      }
+
+      await expectError(
+        addKnowledgeArea(owner, agent, "one-too-many"),
+        "TooManyKnowledgeAreas"
+      );
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.knowledgeAreas.length).to.equal(MAX_KNOWLEDGE_AREAS);
+    });
+  });
+
+  describe("dispute_credential", () => {
+    const dispute = (
+      target: anchor.web3.PublicKey,
+      disputer: { owner: anchor.web3.Keypair; agent: anchor.web3.PublicKey }
+    ) =>
+      program.methods
+        .disputeCredential(new anchor.BN(0), "issuer does not exist")
+        .accountsPartial({
+          targetAgent: target,
+          disputerAgent: disputer.agent,
+          owner: disputer.owner.publicKey,
+          config: configPda,
+        })
+        .signers([disputer.owner])
+        .rpc();
+
+    before(() =>
+      updateConfig({ disputeReputationThreshold: new anchor.BN(100) })
+    );
+    after(() =>
+      updateConfig({ disputeReputationThreshold: new anchor.BN(500) })
+    );
+
+    it("lets a high-reputation agent flag a credential", async () => {
+      const target = await createAgent();
+      await verify(target.owner, target.agent);
+      await addCredential(target.owner, target.agent);
+      const moderator = await createAgent();
+      await addAchievement(moderator.owner, moderator.agent, "Trusted", 150);
+
+      await dispute(target.agent, moderator);
+
+      const account = await program.account.incarraAgent.fetch(target.agent);
+      expect(account.credentials[0].disputed).to.equal(true);
+    });
+
+    it("rejects a disputer below the reputation threshold", async () => {
+      const target = await createAgent();
+      await verify(target.owner, target.agent);
+      await addCredential(target.owner, target.agent);
+      const newcomer = await createAgent();
+
+      await expectError(dispute(target.agent, newcomer), "InsufficientReputation");
+
+      const account = await program.account.incarraAgent.fetch(target.agent);
+      expect(account.credentials[0].disputed).to.equal(false);
+    });
+
+    it("rejects a retired disputer", async () => {
+      const target = await createAgent();
+      await verify(target.owner, target.agent);
+      await addCredential(target.owner, target.agent);
+      const moderator = await createAgent();
+      await addAchievement(moderator.owner, moderator.agent, "Trusted", 150);
+      await program.methods
+        .retireIncarra()
+        .accountsPartial({ incarraAgent: moderator.agent, owner: moderator.owner.publicKey })
+        .signers([moderator.owner])
+        .rpc();
+
+      await expectError(dispute(target.agent, moderator), "AgentRetired");
+
+      const account = await program.account.incarraAgent.fetch(target.agent);
+      expect(account.credentials[0].disputed).to.equal(false);
+    });
+  });
+
+  describe("signed interactions", () => {
+    const requireSigned = (owner: anchor.web3.Keypair, agent: anchor.web3.PublicKey) =>
+      program.methods
+        .setRequireSignedInteractions(true)
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+
+    // Mirrors interaction_message: agent ‖ nonce ‖ type ‖ experience ‖ context
+    const signedInteraction = async (
+      owner: anchor.web3.Keypair,
+      agent: anchor.web3.PublicKey,
+      experience: number
+    ) => {
+      const { totalInteractions } = await program.account.incarraAgent.fetch(agent);
+      const interactionType = { conversation: {} };
+      const message = Buffer.concat([
+        agent.toBuffer(),
+        totalInteractions.toArrayLike(Buffer, "le", 8),
+        program.coder.types.encode("interactionType", interactionType),
+        new anchor.BN(experience).toArrayLike(Buffer, "le", 8),
+        Buffer.from("{}"),
+      ]);
+
+      return program.methods
+        .interactWithIncarra(interactionType, new anchor.BN(experience), "{}", null)
+        .accountsPartial({
+          incarraAgent: agent,
+          recorder: owner.publicKey,
+          config: configPda,
+        })
+        .preInstructions([
+          anchor.web3.Ed25519Program.createInstructionWithPrivateKey({
+            privateKey: owner.secretKey,
+            message,
+          }),
+        ])
+        .signers([owner])
+        .rpc();
+    };
+
+    it("needs no signature while the flag is off", async () => {
+      const { owner, agent } = await createAgent();
+
+      await interact(owner, agent);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.requireSignedInteractions).to.equal(false);
+      expect(account.totalInteractions.toNumber()).to.equal(1);
+    });
+
+    it("accepts an interaction signed by the owner", async () => {
+      const { owner, agent } = await createAgent();
+      await requireSigned(owner, agent);
+
+      await signedInteraction(owner, agent, 10);
+      await signedInteraction(owner, agent, 10);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.totalInteractions.toNumber()).to.equal(2);
+    });
+
+    it("rejects an unsigned interaction once the flag is on", async () => {
+      const { owner, agent } = await createAgent();
+      await requireSigned(owner, agent);
+
+      await expectError(interact(owner, agent), "InteractionSignatureRequired");
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.totalInteractions.toNumber()).to.equal(0);
+    });
+  });
+
+  describe("estimate_time_to_next_level", () => {
+    const estimate = (agent: anchor.web3.PublicKey) =>
+      program.methods
+        .estimateTimeToNextLevel()
+        .accountsPartial({ incarraAgent: agent })
+        .view();
+
+    it("returns null for an agent with no history", async () => {
+      const { agent } = await createAgent();
+
+      expect(await estimate(agent)).to.equal(null);
+    });
+
+    it("projects a finite ETA once experience has accrued", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent, 40);
+      // Let the clock move past created_at so there is a rate to average
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+
+      const eta = await estimate(agent);
+
+      expect(eta).to.not.equal(null);
+      expect(eta.toNumber()).to.be.greaterThan(0);
+    });
+  });
+
+  describe("credential signatures", () => {
+    const borshString = (value: string) => {
+      const bytes = Buffer.from(value);
+      const length = Buffer.alloc(4);
+      length.writeUInt32LE(bytes.length);
+      return Buffer.concat([length, bytes]);
+    };
+
+    // Mirrors credential_payload: agent ‖ type ‖ data ‖ issuer (borsh strings)
+    const payload = (agent: anchor.web3.PublicKey, data: string) =>
+      Buffer.concat([
+        agent.toBuffer(),
+        borshString("Certification"),
+        borshString(data),
+        borshString("Signed Issuer"),
+      ]);
+
+    const signedBy = (issuer: anchor.web3.Keypair, message: Buffer) =>
+      anchor.web3.Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: issuer.secretKey,
+        message,
+      });
+
+    const reverify = (agent: anchor.web3.PublicKey, proof: anchor.web3.TransactionInstruction) =>
+      program.methods
+        .reverifyCredential(new anchor.BN(0))
+        .accountsPartial({ incarraAgent: agent })
+        .preInstructions([proof])
+        .view();
+
+    const addSigned = async (issuer: anchor.web3.Keypair) => {
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+      await program.methods
+        .addVerifiedCredential("Certification", "{}", "Signed Issuer")
+        .accountsPartial({
+          incarraAgent: agent,
+          owner: owner.publicKey,
+          issuer: issuer.publicKey,
+          config: configPda,
+        })
+        .preInstructions([signedBy(issuer, payload(agent, "{}"))])
+        .signers([owner, issuer])
+        .rpc();
+      return agent;
+    };
+
+    it("stores the issuer signature and reverifies it", async () => {
+      const issuer = Keypair.generate();
+      const agent = await addSigned(issuer);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.credentials[0].signedBy.toBase58()).to.equal(
+        issuer.publicKey.toBase58()
+      );
+      expect(account.credentials[0].signature).to.have.length(64);
+
+      expect(await reverify(agent, signedBy(issuer, payload(agent, "{}")))).to.equal(true);
+    });
+
+    it("returns false for a tampered payload", async () => {
+      const issuer = Keypair.generate();
+      const agent = await addSigned(issuer);
+
+      const tampered = payload(agent, '{"grade":"A+"}');
+
+      expect(await reverify(agent, signedBy(issuer, tampered))).to.equal(false);
+    });
+  });
+
+  describe("eligible_for_selection", () => {
+    const eligible = (agent: anchor.web3.PublicKey, seed: number, threshold: number) =>
+      program.methods
+        .eligibleForSelection(new anchor.BN(seed), new anchor.BN(threshold))
+        .accountsPartial({ incarraAgent: agent })
+        .view();
+
+    it("selects higher-reputation agents more often", async () => {
+      const high = await createAgent();
+      await addAchievement(high.owner, high.agent, "Veteran", 1000);
+      const low = await createAgent();
+      await addAchievement(low.owner, low.agent, "Novice", 100);
+
+      let highSelected = 0;
+      let lowSelected = 0;
+      for (let seed = 0; seed < 20; seed++) {
+        if (await eligible(high.agent, seed, 90)) highSelected++;
+        if (await eligible(low.agent, seed, 90)) lowSelected++;
+      }
+
+      expect(highSelected).to.be.greaterThan(lowSelected);
+    });
+
+    it("is deterministic for a fixed seed", async () => {
+      const { owner, agent } = await createAgent();
+      await addAchievement(owner, agent, "Steady", 500);
+
+      const first = await eligible(agent, 42, 250);
+      const second = await eligible(agent, 42, 250);
+
+      expect(second).to.equal(first);
+    });
+  });
+
+  describe("admin_set_experience", () => {
+    const setExperience = (
+      agent: anchor.web3.PublicKey,
+      experience: number,
+      signer?: anchor.web3.Keypair
+    ) =>
+      program.methods
+        .adminSetExperience(new anchor.BN(experience))
+        .accountsPartial({
+          incarraAgent: agent,
+          config: configPda,
+          admin: signer ? signer.publicKey : admin,
+        })
+        .signers(signer ? [signer] : [])
+        .rpc({ commitment: "confirmed" });
+
+    it("lowers the level along with the experience", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent, 350);
+      expect((await program.account.incarraAgent.fetch(agent)).level.toNumber()).to.equal(4);
+
+      const sig = await setExperience(agent, 120);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.experience.toNumber()).to.equal(120);
+      expect(account.level.toNumber()).to.equal(2);
+      expect(hasEvent(await eventsOf(sig), "ExperienceCorrected")).to.equal(true);
+    });
+
+    it("rejects a correction from a non-admin", async () => {
+      const { owner, agent } = await createAgent();
+
+      await expectError(setExperience(agent, 500, owner), "Unauthorized");
+    });
+  });
+
+  describe("update_sequence", () => {
+    const sequenceOf = (agent: anchor.web3.PublicKey) =>
+      program.methods
+        .getUpdateSequence()
+        .accountsPartial({ incarraAgent: agent })
+        .view()
+        .then((sequence: anchor.BN) => sequence.toNumber());
+
+    it("increments exactly once per mutating instruction", async () => {
+      const { owner, agent } = await createAgent();
+      expect(await sequenceOf(agent)).to.equal(0);
+
+      const ownerUpdate = { incarraAgent: agent, owner: owner.publicKey };
+      const mutations: Array<() => Promise<unknown>> = [
+        () => interact(owner, agent),
+        () => verify(owner, agent),
+        () => addCredential(owner, agent),
+        () => addAchievement(owner, agent, "Milestone", 5),
+        () => addKnowledgeArea(owner, agent, "physics"),
+        () =>
+          program.methods
+            .updatePersonality("curious")
+            .accountsPartial(ownerUpdate)
+            .signers([owner])
+            .rpc(),
+        () =>
+          program.methods
+            .recomputeReputation()
+            .accountsPartial(ownerUpdate)
+            .signers([owner])
+            .rpc(),
+        () =>
+          program.methods
+            .deactivateIncarra()
+            .accountsPartial(ownerUpdate)
+            .signers([owner])
+            .rpc(),
+      ];
+
+      for (const [index, mutate] of mutations.entries()) {
+        await mutate();
+        expect(await sequenceOf(agent)).to.equal(index + 1);
+      }
+    });
+
+    it("is unchanged by reads", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent);
+
+      await program.methods
+        .getFullProfile()
+        .accountsPartial({ incarraAgent: agent })
+        .view();
+
+      expect(await sequenceOf(agent)).to.equal(1);
+    });
+  });
+
+  describe("interaction warm-up", () => {
+    const dataAnalysis = { dataAnalysis: {} };
+
+    after(() => updateConfig({ warmupSeconds: new anchor.BN(0) }));
+
+    it("halves reputation inside the warm-up window", async () => {
+      await updateConfig({ warmupSeconds: new anchor.BN(3600) });
+      const { owner, agent } = await createAgent();
+
+      await interact(owner, agent, 10, dataAnalysis);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.interactionReputation.toNumber()).to.equal(2);
+    });
+
+    it("halves the explorer and context bonuses too", async () => {
+      await updateConfig({
+        warmupSeconds: new anchor.BN(3600),
+        explorerBonus: new anchor.BN(4),
+        contextBonusEnabled: true,
+        maxContextBonus: new anchor.BN(2),
+      });
+      try {
+        const { owner, agent } = await createAgent();
+
+        await program.methods
+          .interactWithIncarra(dataAnalysis, new anchor.BN(10), "x".repeat(200), null)
+          .accountsPartial({ incarraAgent: agent, recorder: owner.publicKey, config: configPda })
+          .signers([owner])
+          .rpc();
+
+        // DataAnalysis 5, explorer 4, and context 2, each halved
+        const account = await program.account.incarraAgent.fetch(agent);
+        expect(account.interactionReputation.toNumber()).to.equal(5);
+      } finally {
+        await updateConfig({
+          explorerBonus: new anchor.BN(0),
+          contextBonusEnabled: false,
+          maxContextBonus: new anchor.BN(3),
+        });
+      }
+    });
+
+    it("grants full reputation once the window has passed", async () => {
+      await updateConfig({ warmupSeconds: new anchor.BN(1) });
+      const { owner, agent } = await createAgent();
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+
+      await interact(owner, agent, 10, dataAnalysis);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.interactionReputation.toNumber()).to.equal(5);
+    });
+  });
+
+  describe("get_profiles_batch", () => {
+    const profilesOf = (accounts: anchor.web3.PublicKey[]) =>
+      program.methods
+        .getProfilesBatch()
+        .remainingAccounts(
+          accounts.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false }))
+        )
+        .view();
+
+    it("returns a profile for each agent account passed", async () => {
+      const agents = [];
+      for (let level = 1; level <= 3; level++) {
+        const created = await createAgent();
+        await interact(created.owner, created.agent, (level - 1) * 100);
+        agents.push(created.agent);
+      }
+
+      const profiles = await profilesOf(agents);
+
+      expect(profiles.map((p) => p.level.toNumber())).to.deep.equal([1, 2, 3]);
+      for (const [index, agent] of agents.entries()) {
+        const account = await program.account.incarraAgent.fetch(agent);
+        expect(profiles[index].reputationScore.toNumber()).to.equal(
+          account.reputationScore.toNumber()
+        );
+        expect(profiles[index].carvId).to.equal(account.carvId);
+      }
+    });
+
+    it("skips accounts that aren't agents", async () => {
+      const { agent } = await createAgent();
+
+      const profiles = await profilesOf([configPda, agent, admin]);
+
+      expect(profiles).to.have.length(1);
+    });
+
+    it("rejects more accounts than the batch cap", async () => {
+      const accounts = Array.from({ length: 11 }, () => Keypair.generate().publicKey);
+
+      await expectViewError(profilesOf(accounts), "BatchTooLarge");
+    });
+  });
+
+  describe("reputation decay", () => {
+    before(() =>
+      updateConfig({
+        decayPeriodSeconds: new anchor.BN(3),
+        decayPercentPerPeriod: new anchor.BN(10),
+      })
+    );
+    after(() => updateConfig({ decayPeriodSeconds: new anchor.BN(0) }));
+
+    it("previews the score that applying decay produces", async () => {
+      const { owner, agent } = await createAgent();
+      await addAchievement(owner, agent, "Early Bird", 100);
+      // One full period idle, with margin before the second
+      await new Promise((resolve) => setTimeout(resolve, 4000));
+
+      const preview = await program.methods
+        .previewDecay()
+        .accountsPartial({ incarraAgent: agent, config: configPda })
+        .view();
+      expect((await program.account.incarraAgent.fetch(agent)).reputationScore.toNumber())
+        .to.equal(100);
+
+      await program.methods
+        .applyReputationDecay()
+        .accountsPartial({ incarraAgent: agent, config: configPda })
+        .rpc();
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(preview.toNumber()).to.equal(90);
+      expect(account.reputationScore.toNumber()).to.equal(preview.toNumber());
+      expect(account.decayedReputation.toNumber()).to.equal(10);
+    });
+  });
+
+  describe("credential field limits", () => {
+    it("rejects over-length type, data, and issuer", async () => {
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+
+      await expectError(
+        addCredential(owner, agent, "t".repeat(21)),
+        "CredentialTypeTooLong"
+      );
+      await expectError(
+        addCredential(owner, agent, "Skill", "d".repeat(81)),
+        "CredentialDataTooLong"
+      );
+      await expectError(
+        addCredential(owner, agent, "Skill", "{}", "i".repeat(33)),
+        "CredentialIssuerTooLong"
+      );
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.credentials).to.have.length(0);
+    });
+
+    it("accepts fields at their maximum lengths", async () => {
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+
+      await addCredential(owner, agent, "t".repeat(20), "d".repeat(80), "i".repeat(32));
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.credentials[0].credentialData).to.equal("d".repeat(80));
+    });
+  });
+
+  describe("get_achievement_rarities", () => {
+    it("assigns tiers at each threshold boundary", async () => {
+      const { owner, agent } = await createAgent();
+      const scores = [49, 50, 99, 100, 249, 250];
+      await program.methods
+        .addAchievements(
+          scores.map((score) => ({
+            name: `Score ${score}`,
+            description: "",
+            score: new anchor.BN(score),
+            expiresAt: null,
+          }))
+        )
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+
+      const rarities = await program.methods
+        .getAchievementRarities()
+        .accountsPartial({ incarraAgent: agent, config: configPda })
+        .view();
+
+      expect(rarities.map((r) => Object.keys(r.rarity)[0])).to.deep.equal([
+        "common",
+        "rare",
+        "rare",
+        "epic",
+        "epic",
+        "legendary",
+      ]);
+      expect(rarities[0].name).to.equal("Score 49");
+    });
+  });
+
+  describe("verification signature requirement", () => {
+    it("rejects verification when no signature was stored", async () => {
+      const { owner, agent } = await createAgent("TestBot", "");
+
+      await expectError(verify(owner, agent), "MissingVerificationSignature");
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.carvVerified).to.equal(false);
+    });
+
+    it("verifies when a signature was stored at creation", async () => {
+      const { owner, agent } = await createAgent();
+
+      await verify(owner, agent);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.carvVerified).to.equal(true);
+    });
+  });
+
+  describe("get_reputation_breakdown", () => {
+    const expectBreakdownMatchesScore = async (agent: anchor.web3.PublicKey) => {
+      const breakdown = await program.methods
+        .getReputationBreakdown()
+        .accountsPartial({ incarraAgent: agent })
+        .view();
+      const account = await program.account.incarraAgent.fetch(agent);
+
+      const total =
+        breakdown.fromInteractions.toNumber() +
+        breakdown.fromDataSources.toNumber() +
+        breakdown.fromResearch.toNumber() +
+        breakdown.fromReferrals.toNumber() +
+        breakdown.fromCredentials.toNumber() +
+        breakdown.fromAchievements.toNumber() +
+        breakdown.fromKnowledgeAreas.toNumber() +
+        breakdown.fromVerification.toNumber() -
+        breakdown.lostToDecay.toNumber() -
+        breakdown.lostToPenalties.toNumber();
+      expect(total).to.equal(account.reputationScore.toNumber());
+      return breakdown;
+    };
+
+    it("sums to the score for a new agent", async () => {
+      const { agent } = await createAgent();
+
+      await expectBreakdownMatchesScore(agent);
+    });
+
+    it("sums to the score for an interaction-only agent", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent, 10, { dataAnalysis: {} });
+      await interact(owner, agent, 10, { researchQuery: {} });
+
+      const breakdown = await expectBreakdownMatchesScore(agent);
+      expect(breakdown.fromInteractions.toNumber()).to.equal(8);
+    });
+
+    it("sums to the score for an agent with every source", async () => {
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+      await interact(owner, agent);
+      await addCredential(owner, agent);
+      await addAchievement(owner, agent, "Pioneer", 30);
+      await addKnowledgeArea(owner, agent, "chemistry");
+
+      const breakdown = await expectBreakdownMatchesScore(agent);
+      expect(breakdown.fromVerification.toNumber()).to.equal(50);
+      expect(breakdown.fromCredentials.toNumber()).to.equal(10);
+      expect(breakdown.fromAchievements.toNumber()).to.equal(30);
+      expect(breakdown.fromKnowledgeAreas.toNumber()).to.equal(2);
+    });
+  });
+
+  describe("per-issuer achievement quota", () => {
+    const addIssued = (
+      owner: anchor.web3.Keypair,
+      agent: anchor.web3.PublicKey,
+      issuer: anchor.web3.Keypair,
+      name: string
+    ) =>
+      program.methods
+        .addIssuedAchievement(name, "", new anchor.BN(5), null)
+        .accountsPartial({
+          incarraAgent: agent,
+          owner: owner.publicKey,
+          issuer: issuer.publicKey,
+          config: configPda,
+        })
+        .signers([owner, issuer])
+        .rpc();
+
+    before(() => updateConfig({ maxAchievementsPerIssuer: new anchor.BN(2) }));
+    after(() => updateConfig({ maxAchievementsPerIssuer: new anchor.BN(0) }));
+
+    it("blocks an issuer at its quota while others can still add", async () => {
+      const { owner, agent } = await createAgent();
+      const flooder = Keypair.generate();
+      const other = Keypair.generate();
+
+      await addIssued(owner, agent, flooder, "A");
+      await addIssued(owner, agent, flooder, "B");
+      await expectError(addIssued(owner, agent, flooder, "C"), "IssuerAchievementLimit");
+      await addIssued(owner, agent, other, "D");
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.achievements.map((a) => a.name)).to.deep.equal(["A", "B", "D"]);
+      expect(account.achievements[2].issuer.toBase58()).to.equal(
+        other.publicKey.toBase58()
+      );
+    });
+
+    it("records the owner as issuer of self-added achievements", async () => {
+      const { owner, agent } = await createAgent();
+
+      await addAchievement(owner, agent, "Self", 1);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.achievements[0].issuer.toBase58()).to.equal(
+        owner.publicKey.toBase58()
+      );
+    });
+  });
+
+  describe("daily XP cap", () => {
+    before(() => updateConfig({ dailyXpCap: new anchor.BN(100) }));
+    after(() => updateConfig({ dailyXpCap: new anchor.BN(0) }));
+
+    it("clamps experience once the day's cap is crossed", async () => {
+      const { owner, agent } = await createAgent();
+
+      await interact(owner, agent, 70);
+      const second = await program.methods
+        .interactWithIncarra({ conversation: {} }, new anchor.BN(70), "{}", null)
+        .accountsPartial({ incarraAgent: agent, recorder: owner.publicKey, config: configPda })
+        .signers([owner])
+        .rpc({ commitment: "confirmed" });
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.experience.toNumber()).to.equal(100);
+      expect(account.xpGainedToday.toNumber()).to.equal(100);
+      expect(hasEvent(await eventsOf(second), "DailyXpCapReached")).to.equal(true);
+
+      await interact(owner, agent, 50);
+      const capped = await program.account.incarraAgent.fetch(agent);
+      expect(capped.experience.toNumber()).to.equal(100);
+      expect(capped.totalInteractions.toNumber()).to.equal(3);
+    });
+  });
+
+  describe("get_owner", () => {
+    it("returns the owner from the agent key alone", async () => {
+      const { owner, agent } = await createAgent("Lookup");
+
+      const info = await program.methods
+        .getOwner()
+        .accounts({ incarraAgent: agent })
+        .view();
+
+      expect(info.owner.toBase58()).to.equal(owner.publicKey.toBase58());
+      expect(info.agentName).to.equal("Lookup");
+      expect(info.level.toNumber()).to.equal(1);
+      expect(info.isActive).to.equal(true);
+    });
+
+    it("rejects an account that isn't an agent", async () => {
+      await expectViewError(
+        program.methods.getOwner().accounts({ incarraAgent: configPda }).view(),
+        "AccountDiscriminatorMismatch"
+      );
+    });
+  });
+
+  describe("credential prerequisites", () => {
+    const addAdvanced = (
+      owner: anchor.web3.Keypair,
+      agent: anchor.web3.PublicKey,
+      prerequisite: number
+    ) => addCredential(owner, agent, "Advanced", "{}", "Incarra Academy", prerequisite);
+
+    it("accepts a credential building on a verified one", async () => {
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+      await addVerifiedCredential(owner, agent, Keypair.generate());
+
+      await addAdvanced(owner, agent, 0);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.credentials[1].prerequisiteIndex.toNumber()).to.equal(0);
+    });
+
+    it("rejects an unverified prerequisite", async () => {
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+      await addCredential(owner, agent);
+
+      await expectError(addAdvanced(owner, agent, 0), "PrerequisiteNotMet");
+    });
+
+    it("rejects a prerequisite index with no credential", async () => {
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+
+      await expectError(addAdvanced(owner, agent, 3), "PrerequisiteNotMet");
+    });
+  });
+
+  describe("authorized recorders", () => {
+    const setRecorder = (
+      method: "addRecorder" | "removeRecorder",
+      owner: anchor.web3.Keypair,
+      agent: anchor.web3.PublicKey,
+      recorder: anchor.web3.PublicKey
+    ) =>
+      program.methods[method](recorder)
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+
+    it("lets an authorized recorder interact", async () => {
+      const { owner, agent } = await createAgent();
+      const service = Keypair.generate();
+      await setRecorder("addRecorder", owner, agent, service.publicKey);
+
+      await interact(service, agent);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.totalInteractions.toNumber()).to.equal(1);
+    });
+
+    it("rejects a recorder after removal", async () => {
+      const { owner, agent } = await createAgent();
+      const service = Keypair.generate();
+      await setRecorder("addRecorder", owner, agent, service.publicKey);
+      await setRecorder("removeRecorder", owner, agent, service.publicKey);
+
+      await expectError(interact(service, agent), "UnauthorizedRecorder");
+    });
+
+    it("caps the number of recorders", async () => {
+      const { owner, agent } = await createAgent();
+      for (let i = 0; i < 5; i++) {
+        await setRecorder("addRecorder", owner, agent, Keypair.generate().publicKey);
+      }
+
+      await expectError(
+        setRecorder("addRecorder", owner, agent, Keypair.generate().publicKey),
+        "TooManyRecorders"
+      );
+    });
+  });
+
+  describe("get_profile_fingerprint", () => {
+    const fingerprintOf = (agent: anchor.web3.PublicKey) =>
+      program.methods
+        .getProfileFingerprint()
+        .accountsPartial({ incarraAgent: agent })
+        .view()
+        .then((bytes: number[]) => Buffer.from(bytes).toString("hex"));
+
+    it("is stable across reads", async () => {
+      const { agent } = await createAgent();
+
+      expect(await fingerprintOf(agent)).to.equal(await fingerprintOf(agent));
+    });
+
+    it("changes after a mutating instruction", async () => {
+      const { owner, agent } = await createAgent();
+      const before = await fingerprintOf(agent);
+
+      await addKnowledgeArea(owner, agent, "linguistics");
+
+      expect(await fingerprintOf(agent)).to.not.equal(before);
+    });
+  });
+
+  describe("unverified credential spam penalty", () => {
+    before(() =>
+      updateConfig({
+        unverifiedCredentialThreshold: new anchor.BN(2),
+        unverifiedCredentialPenalty: new anchor.BN(5),
+      })
+    );
+    after(() => updateConfig({ unverifiedCredentialThreshold: new anchor.BN(0) }));
+
+    it("rewards credentials up to the threshold and penalizes the rest", async () => {
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+      const start = (await program.account.incarraAgent.fetch(agent)).reputationScore;
+
+      await addCredential(owner, agent, "First");
+      await addCredential(owner, agent, "Second");
+      const rewarded = (await program.account.incarraAgent.fetch(agent)).reputationScore;
+      expect(rewarded.sub(start).toNumber()).to.equal(20);
+
+      const sig = await program.methods
+        .addCredential("Third", "{}", "Incarra Academy", null)
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey, config: configPda })
+        .signers([owner])
+        .rpc({ commitment: "confirmed" });
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(rewarded.sub(account.reputationScore).toNumber()).to.equal(5);
+      expect(account.credentials[2].spamPenalized).to.equal(true);
+      expect(hasEvent(await eventsOf(sig), "CredentialSpamPenalty")).to.equal(true);
+    });
+
+    it("does not count verified credentials toward the threshold", async () => {
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+      for (let i = 0; i < 3; i++) {
+        await addVerifiedCredential(owner, agent, Keypair.generate());
+      }
+
+      await addCredential(owner, agent);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.credentials.some((c) => c.spamPenalized)).to.equal(false);
+    });
+  });
+
+  describe("set_personality_versioned", () => {
+    const setPersonality = (
+      owner: anchor.web3.Keypair,
+      agent: anchor.web3.PublicKey,
+      personality: string
+    ) =>
+      program.methods
+        .setPersonalityVersioned(personality)
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+
+    it("increments the version on each change", async () => {
+      const { owner, agent } = await createAgent();
+
+      await setPersonality(owner, agent, "curious");
+      await setPersonality(owner, agent, "focused");
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.personality).to.equal("focused");
+      expect(account.personalityVersion).to.equal(2);
+    });
+
+    it("keeps only the most recent previous personalities", async () => {
+      const { owner, agent } = await createAgent();
+
+      for (const personality of ["one", "two", "three", "four"]) {
+        await setPersonality(owner, agent, personality);
+      }
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.personalityHistory).to.deep.equal(["one", "two", "three"]);
+    });
+
+    it("drops the oldest entries in order when pushed well past the cap", async () => {
+      const { owner, agent } = await createAgent();
+
+      for (const personality of ["a", "b", "c", "d", "e", "f"]) {
+        await setPersonality(owner, agent, personality);
+      }
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.personality).to.equal("f");
+      expect(account.personalityHistory).to.deep.equal(["c", "d", "e"]);
+      expect(account.personalityVersion).to.equal(6);
+    });
+
+    it("leaves state unchanged when validation fails", async () => {
+      const { owner, agent } = await createAgent();
+
+      await expectError(setPersonality(owner, agent, ""), "PersonalityTooShort");
+      await expectError(setPersonality(owner, agent, "x".repeat(201)), "PersonalityTooLong");
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.personality).to.equal("Helpful AI assistant");
+      expect(account.personalityHistory).to.have.length(0);
+      expect(account.personalityVersion).to.equal(0);
+    });
+  });
+
+  describe("is_at_any_cap", () => {
+    const atAnyCap = (agent: anchor.web3.PublicKey) =>
+      program.methods.isAtAnyCap().accountsPartial({ incarraAgent: agent }).view();
+
+    const fillCredentials = async (owner: anchor.web3.Keypair, agent: anchor.web3.PublicKey) => {
+      await verify(owner, agent);
+      for (let i = 0; i < 10; i++) {
+        await addCredential(owner, agent, `Type ${i}`);
+      }
+    };
+
+    it("is false for an agent at no caps", async () => {
+      const { owner, agent } = await createAgent();
+      await addKnowledgeArea(owner, agent, "history");
+
+      expect(await atAnyCap(agent)).to.equal(false);
+    });
+
+    it("is true at the credential cap alone", async () => {
+      const { owner, agent } = await createAgent();
+      await fillCredentials(owner, agent);
+
+      expect(await atAnyCap(agent)).to.equal(true);
+    });
+
+    it("is true at every cap", async () => {
+      const { owner, agent } = await createAgent();
+      await fillCredentials(owner, agent);
+      for (let i = 0; i < 20; i++) {
+        await addAchievement(owner, agent, `Badge ${i}`, 1);
+        await addKnowledgeArea(owner, agent, `area-${String(i).padStart(2, "0")}`);
+      }
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.achievements).to.have.length(20);
+      expect(account.knowledgeAreas).to.have.length(20);
+      expect(await atAnyCap(agent)).to.equal(true);
+    });
+  });
+
+  describe("connect_data_source", () => {
+    const connect = (
+      owner: anchor.web3.Keypair,
+      agent: anchor.web3.PublicKey,
+      sourceType: string
+    ) =>
+      program.methods
+        .connectDataSource(sourceType)
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey, config: configPda })
+        .signers([owner])
+        .rpc();
+
+    const scoreOf = async (agent: anchor.web3.PublicKey) =>
+      (await program.account.incarraAgent.fetch(agent)).reputationScore.toNumber();
+
+    it("grants the diversity bonus for a new source type", async () => {
+      const { owner, agent } = await createAgent();
+
+      await connect(owner, agent, "github");
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.reputationScore.toNumber()).to.equal(7);
+      expect(account.dataSourceTypes).to.deep.equal(["github"]);
+      expect(account.dataSourcesConnected.toNumber()).to.equal(1);
+    });
+
+    it("grants only the base reward for a duplicate type", async () => {
+      const { owner, agent } = await createAgent();
+      await connect(owner, agent, "github");
+      const before = await scoreOf(agent);
+
+      await connect(owner, agent, "github");
+
+      expect((await scoreOf(agent)) - before).to.equal(2);
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.dataSourceTypes).to.have.length(1);
+    });
+  });
+
+  describe("verification proof format", () => {
+    it("rejects short and low-entropy proofs", async () => {
+      const { owner, agent } = await createAgent();
+
+      await expectError(verify(owner, agent, "proof-of-carv-ownership"), "InvalidVerificationProof");
+      await expectError(verify(owner, agent, "ab".repeat(16)), "InvalidVerificationProof");
+      await expectError(verify(owner, agent, "a".repeat(64)), "InvalidVerificationProof");
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.carvVerified).to.equal(false);
+    });
+
+    it("accepts a well-formed 64-hex-character proof", async () => {
+      const { owner, agent } = await createAgent();
+
+      await verify(owner, agent, "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08");
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.carvVerified).to.equal(true);
+    });
+  });
+
+  describe("rank_in_cohort", () => {
+    const rankOf = (target: anchor.web3.PublicKey, cohort: anchor.web3.PublicKey[]) =>
+      program.methods
+        .rankInCohort()
+        .accountsPartial({ incarraAgent: target })
+        .remainingAccounts(
+          cohort.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false }))
+        )
+        .view()
+        .then((rank: anchor.BN) => rank.toNumber());
+
+    let low: anchor.web3.PublicKey;
+    let middle: anchor.web3.PublicKey;
+    let high: anchor.web3.PublicKey;
+    let cohort: anchor.web3.PublicKey[];
+
+    before(async () => {
+      const agentWithScore = async (score: number) => {
+        const { owner, agent } = await createAgent();
+        await addAchievement(owner, agent, "Seed", score);
+        return agent;
+      };
+      low = await agentWithScore(10);
+      middle = await agentWithScore(20);
+      high = await agentWithScore(30);
+      cohort = [low, middle, high];
+    });
+
+    it("ranks the highest agent first", async () => {
+      expect(await rankOf(high, cohort)).to.equal(1);
+    });
+
+    it("ranks the lowest agent last", async () => {
+      expect(await rankOf(low, cohort)).to.equal(3);
+    });
+
+    it("ranks a middle agent between the others", async () => {
+      expect(await rankOf(middle, cohort)).to.equal(2);
+    });
+  });
+
+  describe("spend_experience", () => {
+    const spend = (owner: anchor.web3.Keypair, agent: anchor.web3.PublicKey, amount: number) =>
+      program.methods
+        .spendExperience(new anchor.BN(amount), "profile theme")
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey })
+        .signers([owner])
+        .rpc({ commitment: "confirmed" });
+
+    it("deducts spent experience and emits ExperienceSpent", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent, 150);
+
+      const sig = await spend(owner, agent, 60);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.experience.toNumber()).to.equal(90);
+      expect(hasEvent(await eventsOf(sig), "ExperienceSpent")).to.equal(true);
+    });
+
+    it("rejects spending more than the balance", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent, 40);
+
+      await expectError(spend(owner, agent, 41), "InsufficientExperience");
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.experience.toNumber()).to.equal(40);
+    });
+
+    it("does not lower the level", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent, 250);
+
+      await spend(owner, agent, 200);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.level.toNumber()).to.equal(3);
+      expect(account.lifetimeExperience.toNumber()).to.equal(250);
+    });
+  });
+
+  describe("lifetime experience", () => {
+    const contextOf = (agent: anchor.web3.PublicKey) =>
+      program.methods.getIncarraContext().accountsPartial({ incarraAgent: agent }).view();
+
+    it("tracks earned experience in both totals", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent, 120);
+
+      const context = await contextOf(agent);
+      expect(context.experience.toNumber()).to.equal(120);
+      expect(context.lifetimeExperience.toNumber()).to.equal(120);
+    });
+
+    it("keeps lifetime experience and level when experience is spent", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent, 220);
+
+      await program.methods
+        .spendExperience(new anchor.BN(150), "boost")
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+
+      const context = await contextOf(agent);
+      expect(context.experience.toNumber()).to.equal(70);
+      expect(context.lifetimeExperience.toNumber()).to.equal(220);
+      expect(context.level.toNumber()).to.equal(3);
+
+      // Level-ups keep following the lifetime total
+      await interact(owner, agent, 80);
+      const after = await contextOf(agent);
+      expect(after.level.toNumber()).to.equal(4);
+      expect(after.experience.toNumber()).to.equal(150);
+    });
+  });
+
+  describe("credential bonus table", () => {
+    const gainFor = async (credentialType: string) => {
+      const { owner, agent } = await createAgent();
+      await addCredential(owner, agent, credentialType);
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.credentials[0].typeReputation.toNumber()).to.equal(
+        account.reputationScore.toNumber()
+      );
+      return account.reputationScore.toNumber();
+    };
+
+    it("grants the configured bonus for each listed type", async () => {
+      expect(await gainFor("Education")).to.equal(15);
+      expect(await gainFor("Skill")).to.equal(10);
+      expect(await gainFor("Certification")).to.equal(20);
+    });
+
+    it("falls back to the base bonus for an unknown type", async () => {
+      expect(await gainFor("Hobby")).to.equal(10);
+    });
+
+    it("uses an updated table and rejects oversized ones", async () => {
+      const original = (await program.account.config.fetch(configPda)).credentialBonuses;
+      await updateConfig({
+        credentialBonuses: [{ credentialType: "Hobby", reputation: new anchor.BN(3) }],
+      });
+      try {
+        expect(await gainFor("Hobby")).to.equal(3);
+        expect(await gainFor("Education")).to.equal(10);
+
+        const tooMany = Array.from({ length: 9 }, (_, i) => ({
+          credentialType: `Type ${i}`,
+          reputation: new anchor.BN(1),
+        }));
+        await expectError(
+          updateConfig({ credentialBonuses: tooMany }),
+          "TooManyCredentialBonuses"
+        );
+      } finally {
+        await updateConfig({ credentialBonuses: original });
+      }
+    });
+  });
+
+  describe("verification validity window", () => {
+    before(() => updateConfig({ verificationValiditySeconds: new anchor.BN(2) }));
+    after(() => updateConfig({ verificationValiditySeconds: new anchor.BN(0) }));
+
+    const isCurrent = (agent: anchor.web3.PublicKey) =>
+      program.methods
+        .isVerificationCurrent()
+        .accountsPartial({ incarraAgent: agent, config: configPda })
+        .view();
+
+    it("treats a fresh verification as current", async () => {
+      const { owner, agent } = await createAgent();
+      expect(await isCurrent(agent)).to.equal(false);
+
+      await verify(owner, agent);
+
+      expect(await isCurrent(agent)).to.equal(true);
+      await addCredential(owner, agent);
+    });
+
+    it("gates credentials on an expired verification until re-verified", async () => {
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+      await new Promise((resolve) => setTimeout(resolve, 4000));
+
+      expect(await isCurrent(agent)).to.equal(false);
+      await expectError(addCredential(owner, agent), "InsufficientActivity");
+
+      const result = await returnOf(await verify(owner, agent), "verificationResult");
+      expect(result.reputationGained.toNumber()).to.equal(0);
+      expect(await isCurrent(agent)).to.equal(true);
+      await addCredential(owner, agent);
+    });
+
+    it("drops the Verified role once the verification expires", async () => {
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+      const qualifies = () =>
+        program.methods
+          .checkQualification({ verified: {} })
+          .accountsPartial({ incarraAgent: agent, config: configPda })
+          .view();
+
+      expect(await qualifies()).to.equal(true);
+      await new Promise((resolve) => setTimeout(resolve, 4000));
+      expect(await qualifies()).to.equal(false);
+    });
+  });
+
+  describe("chain attestations", () => {
+    const attest = (
+      owner: anchor.web3.Keypair,
+      agent: anchor.web3.PublicKey,
+      chainId: number,
+      fill: number
+    ) =>
+      program.methods
+        .addChainAttestation(new anchor.BN(chainId), Array(32).fill(fill))
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey, config: configPda })
+        .signers([owner])
+        .rpc();
+
+    it("records attestations from several chains in order", async () => {
+      const { owner, agent } = await createAgent();
+
+      await attest(owner, agent, 1, 0xaa);
+      await attest(owner, agent, 8453, 0xbb);
+
+      const attestations = await program.methods
+        .getChainAttestations()
+        .accountsPartial({ incarraAgent: agent })
+        .view();
+      expect(attestations.map((a) => a.chainId.toNumber())).to.deep.equal([1, 8453]);
+      expect(attestations[1].attestationHash).to.deep.equal(Array(32).fill(0xbb));
+      expect(attestations[0].recordedAt.toNumber()).to.be.greaterThan(0);
+    });
+
+    it("rejects attestations past the cap", async () => {
+      const { owner, agent } = await createAgent();
+      for (let i = 0; i < 8; i++) {
+        await attest(owner, agent, i + 1, i);
+      }
+
+      await expectError(attest(owner, agent, 99, 0xff), "TooManyChainAttestations");
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.chainAttestations).to.have.length(8);
+    });
+
+    describe("with a per-chain limit", () => {
+      before(() => updateConfig({ maxAttestationsPerChain: new anchor.BN(2) }));
+      after(() => updateConfig({ maxAttestationsPerChain: new anchor.BN(0) }));
+
+      it("blocks a chain at its quota while another chain can still add", async () => {
+        const { owner, agent } = await createAgent();
+        await attest(owner, agent, 1, 0x01);
+        await attest(owner, agent, 1, 0x02);
+
+        await expectError(attest(owner, agent, 1, 0x03), "ChainAttestationLimit");
+        await attest(owner, agent, 8453, 0x04);
+
+        const account = await program.account.incarraAgent.fetch(agent);
+        expect(account.chainAttestations.map((a) => a.chainId.toNumber())).to.deep.equal([
+          1, 1, 8453,
+        ]);
+      });
+    });
+  });
+
+  describe("verified cooldown", () => {
+    before(() =>
+      updateConfig({
+        interactionCooldownSeconds: new anchor.BN(10),
+        verifiedCooldownSeconds: new anchor.BN(2),
+      })
+    );
+    after(() =>
+      updateConfig({
+        interactionCooldownSeconds: new anchor.BN(0),
+        verifiedCooldownSeconds: new anchor.BN(0),
+      })
+    );
+
+    it("lets a verified agent interact again after the shorter cooldown", async () => {
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+      await interact(owner, agent);
+
+      await expectError(interact(owner, agent), "InteractionCooldownActive");
+      await new Promise((resolve) => setTimeout(resolve, 3000));
+      await interact(owner, agent);
+    });
+
+    it("keeps an unverified agent on the longer cooldown", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent);
+
+      await new Promise((resolve) => setTimeout(resolve, 3000));
+      await expectError(interact(owner, agent), "InteractionCooldownActive");
+    });
+  });
+
+  describe("filter_dormant", () => {
+    const dormantAmong = (agents: anchor.web3.PublicKey[], idleSeconds: number) =>
+      program.methods
+        .filterDormant(new anchor.BN(idleSeconds))
+        .remainingAccounts(
+          agents.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false }))
+        )
+        .view()
+        .then((keys: anchor.web3.PublicKey[]) => keys.map((key) => key.toBase58()));
+
+    it("returns only the agents idle past the threshold", async () => {
+      const dormant = await createAgent();
+      const alsoDormant = await createAgent();
+      await new Promise((resolve) => setTimeout(resolve, 3000));
+      const active = await createAgent();
+
+      const keys = await dormantAmong(
+        [dormant.agent, active.agent, alsoDormant.agent],
+        2
+      );
+
+      expect(keys).to.deep.equal([dormant.agent.toBase58(), alsoDormant.agent.toBase58()]);
+    });
+
+    it("rejects scans past the batch limit", async () => {
+      const agents = Array.from({ length: 11 }, () => Keypair.generate().publicKey);
+
+      await expectViewError(dormantAmong(agents, 0), "BatchTooLarge");
+    });
+  });
+
+  describe("penalty cooldown", () => {
+    before(() => updateConfig({ penaltyCooldownSeconds: new anchor.BN(2) }));
+    after(() => updateConfig({ penaltyCooldownSeconds: new anchor.BN(0) }));
+
+    const penalize = (agent: anchor.web3.PublicKey, amount: number) =>
+      program.methods
+        .penalizeAgent(new anchor.BN(amount), "spam")
+        .accountsPartial({ incarraAgent: agent, config: configPda, admin })
+        .rpc();
+
+    it("blocks a second penalty within the window", async () => {
+      const { owner, agent } = await createAgent();
+      await addAchievement(owner, agent, "Seed", 100);
+
+      await penalize(agent, 10);
+      await expectError(penalize(agent, 10), "PenaltyCooldownActive");
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.reputationScore.toNumber()).to.equal(90);
+      expect(account.penalizedReputation.toNumber()).to.equal(10);
+    });
+
+    it("allows another penalty once the window has passed", async () => {
+      const { owner, agent } = await createAgent();
+      await addAchievement(owner, agent, "Seed", 100);
+
+      await penalize(agent, 10);
+      await new Promise((resolve) => setTimeout(resolve, 3000));
+      await penalize(agent, 10);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.reputationScore.toNumber()).to.equal(80);
+    });
+  });
+
+  describe("get_knowledge_timeline", () => {
+    it("lists areas in the order they were added with their timestamps", async () => {
+      const { owner, agent } = await createAgent();
+      await addKnowledgeArea(owner, agent, "rust");
+      await new Promise((resolve) => setTimeout(resolve, 1500));
+      await addKnowledgeArea(owner, agent, "anchor");
+
+      const timeline = await program.methods
+        .getKnowledgeTimeline()
+        .accountsPartial({ incarraAgent: agent })
+        .view();
+
+      expect(timeline.map((entry) => entry.name)).to.deep.equal(["rust", "anchor"]);
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(timeline[0].addedAt.toNumber()).to.be.at.least(account.createdAt.toNumber());
+      expect(timeline[1].addedAt.toNumber()).to.be.greaterThan(timeline[0].addedAt.toNumber());
+    });
+  });
+
+  describe("complete_research_project", () => {
+    const complete = (owner: anchor.web3.Keypair, agent: anchor.web3.PublicKey, title: string) =>
+      program.methods
+        .completeResearchProject(title, Array(32).fill(7))
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey, config: configPda })
+        .signers([owner])
+        .rpc();
+
+    it("records the project and grants the configured reputation", async () => {
+      const { owner, agent } = await createAgent();
+
+      await complete(owner, agent, "Protein folding survey");
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.researchProjectsList).to.have.length(1);
+      expect(account.researchProjectsList[0].title).to.equal("Protein folding survey");
+      expect(account.researchProjectsList[0].resultHash).to.deep.equal(Array(32).fill(7));
+      expect(account.reputationScore.toNumber()).to.equal(8);
+    });
+
+    it("keeps the counter in step with the list", async () => {
+      const { owner, agent } = await createAgent();
+
+      await complete(owner, agent, "First");
+      await complete(owner, agent, "Second");
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.researchProjects.toNumber()).to.equal(2);
+      expect(account.researchProjectsList.map((p) => p.title)).to.deep.equal(["First", "Second"]);
+    });
+
+    it("rejects projects past the cap", async () => {
+      const { owner, agent } = await createAgent();
+      for (let i = 0; i < 5; i++) {
+        await complete(owner, agent, `Project ${i}`);
+      }
+
+      await expectError(complete(owner, agent, "One too many"), "TooManyResearchProjects");
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.researchProjects.toNumber()).to.equal(5);
+    });
+  });
+
+  describe("retire_incarra", () => {
+    const ownerCall = (method: string, owner: anchor.web3.Keypair, agent: anchor.web3.PublicKey) =>
+      program.methods[method]()
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+
+    it("blocks reactivation", async () => {
+      const { owner, agent } = await createAgent();
+
+      await ownerCall("retireIncarra", owner, agent);
+
+      await expectError(ownerCall("reactivateIncarra", owner, agent), "AgentRetired");
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.retired).to.equal(true);
+      expect(account.isActive).to.equal(false);
+    });
+
+    it("blocks mutations while reads still work", async () => {
+      const { owner, agent } = await createAgent();
+      await addKnowledgeArea(owner, agent, "history");
+      await ownerCall("retireIncarra", owner, agent);
+
+      await expectError(interact(owner, agent), "AgentRetired");
+      await expectError(addKnowledgeArea(owner, agent, "math"), "AgentRetired");
+      await expectError(addAchievement(owner, agent, "Late", 5), "AgentRetired");
+      await expectError(verify(owner, agent), "AgentRetired");
+
+      const context = await program.methods
+        .getIncarraContext()
+        .accountsPartial({ incarraAgent: agent })
+        .view();
+      expect(context.knowledgeAreas).to.deep.equal(["history"]);
+    });
+
+    it("still allows reactivating a deactivated agent", async () => {
+      const { owner, agent } = await createAgent();
+
+      await ownerCall("deactivateIncarra", owner, agent);
+      await ownerCall("reactivateIncarra", owner, agent);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.isActive).to.equal(true);
+    });
+  });
+
+  describe("reputation multiplier", () => {
+    const gainAt = async (multiplierBps: number) => {
+      const { owner, agent } = await createAgent();
+      await program.methods
+        .setReputationMultiplier(multiplierBps)
+        .accountsPartial({ incarraAgent: agent, config: configPda, admin })
+        .rpc();
+
+      await interact(owner, agent, 10, { problemSolving: {} });
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      return account.reputationScore.toNumber();
+    };
+
+    it("scales interaction reputation by the agent's multiplier", async () => {
+      expect(await gainAt(10_000)).to.equal(4);
+      expect(await gainAt(15_000)).to.equal(6);
+      expect(await gainAt(20_000)).to.equal(8);
+    });
+
+    it("defaults new agents to 1.0x", async () => {
+      const { agent } = await createAgent();
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.reputationMultiplierBps).to.equal(10_000);
+    });
+
+    it("rejects a multiplier set by a non-admin", async () => {
+      const { agent } = await createAgent();
+      const outsider = Keypair.generate();
+
+      await expectError(
+        program.methods
+          .setReputationMultiplier(20_000)
+          .accountsPartial({ incarraAgent: agent, config: configPda, admin: outsider.publicKey })
+          .signers([outsider])
+          .rpc(),
+        "Unauthorized"
+      );
+    });
+  });
+
+  describe("get_serialized_size", () => {
+    const sizeOf = (agent: anchor.web3.PublicKey) =>
+      program.methods
+        .getSerializedSize()
+        .accountsPartial({ incarraAgent: agent })
+        .view()
+        .then((size: anchor.BN) => size.toNumber());
+
+    it("grows with dynamic content and stays within the allocation", async () => {
+      const { owner, agent } = await createAgent();
+      const empty = await sizeOf(agent);
+
+      await addKnowledgeArea(owner, agent, "rust");
+
+      // 4-byte length prefix + 4 characters + 8-byte added_at
+      expect((await sizeOf(agent)) - empty).to.equal(16);
+      const allocated = await program.methods
+        .getAccountSize()
+        .accountsPartial({ incarraAgent: agent })
+        .view();
+      expect(empty).to.be.lessThan(allocated.toNumber());
+    });
+  });
+
+  describe("minimum level for achievements", () => {
+    before(() => updateConfig({ minLevelForAchievements: new anchor.BN(3) }));
+    after(() => updateConfig({ minLevelForAchievements: new anchor.BN(0) }));
+
+    it("rejects achievements below the required level", async () => {
+      const { owner, agent } = await createAgent();
+
+      await expectError(addAchievement(owner, agent, "Too Early", 10), "LevelTooLowForAchievement");
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.achievements).to.have.length(0);
+    });
+
+    it("accepts achievements at the required level", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent, 200);
+
+      await addAchievement(owner, agent, "On Time", 10);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.level.toNumber()).to.equal(3);
+      expect(account.achievements).to.have.length(1);
+    });
+  });
+
+  describe("reputation_percentile", () => {
+    const percentileOf = (target: anchor.web3.PublicKey, cohort: anchor.web3.PublicKey[]) =>
+      program.methods
+        .reputationPercentile()
+        .accountsPartial({ incarraAgent: target })
+        .remainingAccounts(
+          cohort.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false }))
+        )
+        .view()
+        .then((bps: anchor.BN) => bps.toNumber());
+
+    let bottom: anchor.web3.PublicKey;
+    let median: anchor.web3.PublicKey;
+    let top: anchor.web3.PublicKey;
+    let cohort: anchor.web3.PublicKey[];
+
+    before(async () => {
+      const agentWithScore = async (score: number) => {
+        const { owner, agent } = await createAgent();
+        await addAchievement(owner, agent, "Seed", score);
+        return agent;
+      };
+      bottom = await agentWithScore(10);
+      median = await agentWithScore(20);
+      top = await agentWithScore(30);
+      cohort = [bottom, median, top];
+    });
+
+    it("puts the top agent at 10000 bps", async () => {
+      expect(await percentileOf(top, cohort)).to.equal(10_000);
+    });
+
+    it("puts the bottom agent at 0 bps", async () => {
+      expect(await percentileOf(bottom, cohort)).to.equal(0);
+    });
+
+    it("puts the median agent at 5000 bps", async () => {
+      expect(await percentileOf(median, cohort)).to.equal(5_000);
+    });
+  });
+
+  describe("commit-reveal Carv ID linking", () => {
+    const NEW_CARV_ID = "0x" + "cd".repeat(20);
+    const SALT = Array.from({ length: 32 }, (_, i) => i);
+
+    const commitmentOf = (agent: anchor.web3.PublicKey, carvId: string, salt: number[]) =>
+      Array.from(
+        createHash("sha256")
+          .update(Buffer.concat([agent.toBuffer(), Buffer.from(carvId), Buffer.from(salt)]))
+          .digest()
+      );
+
+    const commit = (owner: anchor.web3.Keypair, agent: anchor.web3.PublicKey, hash: number[]) =>
+      program.methods
+        .commitCarvId(hash)
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+
+    const reveal = (
+      owner: anchor.web3.Keypair,
+      agent: anchor.web3.PublicKey,
+      carvId: string,
+      salt: number[]
+    ) =>
+      program.methods
+        .revealCarvId(carvId, salt)
+        .accountsPartial({
+          incarraAgent: agent,
+          carvIndex: carvIndexPda(CARV_ID),
+          owner: owner.publicKey,
+        })
+        .signers([owner])
+        .rpc();
+
+    it("links the Carv ID on a matching reveal", async () => {
+      const { owner, agent } = await createAgent();
+
+      await commit(owner, agent, commitmentOf(agent, NEW_CARV_ID, SALT));
+      await reveal(owner, agent, NEW_CARV_ID, SALT);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.carvId).to.equal(NEW_CARV_ID);
+      expect(account.carvIdCommitment).to.equal(null);
+    });
+
+    it("rejects a reveal that doesn't match the commitment", async () => {
+      const { owner, agent } = await createAgent();
+      await commit(owner, agent, commitmentOf(agent, NEW_CARV_ID, SALT));
+
+      await expectError(
+        reveal(owner, agent, NEW_CARV_ID, SALT.map((b) => b ^ 1)),
+        "CarvCommitmentMismatch"
+      );
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.carvId).to.equal(CARV_ID);
+    });
+
+    it("rejects a commitment copied from another agent", async () => {
+      const first = await createAgent();
+      const second = await createAgent();
+      const hash = commitmentOf(first.agent, NEW_CARV_ID, SALT);
+      await commit(first.owner, first.agent, hash);
+
+      await commit(second.owner, second.agent, hash);
+
+      await expectError(
+        reveal(second.owner, second.agent, NEW_CARV_ID, SALT),
+        "CarvCommitmentMismatch"
+      );
+    });
+
+    it("rejects a reveal without a commitment", async () => {
+      const { owner, agent } = await createAgent();
+
+      await expectError(reveal(owner, agent, NEW_CARV_ID, SALT), "NoCarvCommitment");
+    });
+  });
+
+  describe("verified achievement multiplier", () => {
+    before(() => updateConfig({ verifiedAchievementMultiplierBps: new anchor.BN(15_000) }));
+    after(() => updateConfig({ verifiedAchievementMultiplierBps: new anchor.BN(10_000) }));
+
+    const gainFromAchievement = async (verified: boolean) => {
+      const { owner, agent } = await createAgent();
+      if (verified) {
+        await verify(owner, agent);
+      }
+      const before = (await program.account.incarraAgent.fetch(agent)).reputationScore;
+
+      await addAchievement(owner, agent, "Hackathon Winner", 40);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.achievements[0].score.toNumber()).to.equal(40);
+      return account.reputationScore.sub(before).toNumber();
+    };
+
+    it("scales achievement reputation for verified agents only", async () => {
+      expect(await gainFromAchievement(false)).to.equal(40);
+      expect(await gainFromAchievement(true)).to.equal(60);
+    });
+  });
+
+  describe("get_interaction_buckets", () => {
+    const bucketsOf = (agent: anchor.web3.PublicKey, bucketSeconds: number, now: number) =>
+      program.methods
+        .getInteractionBuckets(new anchor.BN(bucketSeconds), new anchor.BN(now))
+        .accountsPartial({ incarraAgent: agent })
+        .view()
+        .then((buckets: anchor.BN[]) => buckets.map((count) => count.toNumber()));
+
+    it("buckets recorded interactions back from now", async () => {
+      const { owner, agent } = await createAgent();
+      for (let i = 0; i < 3; i++) {
+        await interact(owner, agent);
+      }
+      const account = await program.account.incarraAgent.fetch(agent);
+      const last = account.recentInteractions[2].toNumber();
+
+      expect(await bucketsOf(agent, 100, last)).to.deep.equal([3]);
+      expect(await bucketsOf(agent, 100, last + 250)).to.deep.equal([0, 0, 3]);
+    });
+
+    it("rejects a non-positive bucket width", async () => {
+      const { agent } = await createAgent();
+
+      await expectViewError(bucketsOf(agent, 0, 0), "InvalidBucketWidth");
+    });
+  });
+
+  describe("decay exemption for verified credentials", () => {
+    before(() =>
+      updateConfig({
+        decayPeriodSeconds: new anchor.BN(3),
+        decayPercentPerPeriod: new anchor.BN(10),
+        decayExemptVerifiedCredentials: true,
+      })
+    );
+    after(() =>
+      updateConfig({
+        decayPeriodSeconds: new anchor.BN(0),
+        decayExemptVerifiedCredentials: false,
+      })
+    );
+
+    const decayedAfterIdle = async (agent: anchor.web3.PublicKey) => {
+      await program.methods
+        .applyReputationDecay()
+        .accountsPartial({ incarraAgent: agent, config: configPda })
+        .rpc();
+      const account = await program.account.incarraAgent.fetch(agent);
+      return account.decayedReputation.toNumber();
+    };
+
+    it("skips decay for holders of verified credentials only", async () => {
+      const holder = await createAgent();
+      await verify(holder.owner, holder.agent);
+      await addVerifiedCredential(holder.owner, holder.agent, Keypair.generate());
+      await addAchievement(holder.owner, holder.agent, "Early Bird", 100);
+
+      const other = await createAgent();
+      await verify(other.owner, other.agent);
+      await addCredential(other.owner, other.agent);
+      await addAchievement(other.owner, other.agent, "Early Bird", 100);
+
+      await new Promise((resolve) => setTimeout(resolve, 4000));
+
+      expect(await decayedAfterIdle(holder.agent)).to.equal(0);
+      expect(await decayedAfterIdle(other.agent)).to.be.greaterThan(0);
+    });
+  });
+
+  describe("transfer_achievement", () => {
+    type Agent = { owner: anchor.web3.Keypair; agent: anchor.web3.PublicKey };
+    const transfer = (from: Agent, to: Agent, index: number, destinationOwner = to.owner) =>
+      program.methods
+        .transferAchievement(new anchor.BN(index))
+        .accountsPartial({
+          sourceAgent: from.agent,
+          destinationAgent: to.agent,
+          owner: from.owner.publicKey,
+          destinationOwner: destinationOwner.publicKey,
+          config: configPda,
+        })
+        .signers([from.owner, destinationOwner])
+        .rpc({ commitment: "confirmed" });
+
+    it("moves the achievement and emits AchievementTransferred", async () => {
+      const from = await createAgent();
+      const to = await createAgent();
+      await addAchievement(from.owner, from.agent, "Keep", 5);
+      await addAchievement(from.owner, from.agent, "Shared Paper", 30);
+
+      const sig = await transfer(from, to, 1);
+
+      const source = await program.account.incarraAgent.fetch(from.agent);
+      const destination = await program.account.incarraAgent.fetch(to.agent);
+      expect(source.achievements.map((a) => a.name)).to.deep.equal(["Keep"]);
+      expect(destination.achievements.map((a) => a.name)).to.deep.equal(["Shared Paper"]);
+      expect(hasEvent(await eventsOf(sig), "AchievementTransferred")).to.equal(true);
+    });
+
+    it("adjusts reputation on both sides", async () => {
+      const from = await createAgent();
+      const to = await createAgent();
+      await addAchievement(from.owner, from.agent, "Shared Paper", 30);
+      await addAchievement(to.owner, to.agent, "Own Work", 10);
+
+      await transfer(from, to, 0);
+
+      const source = await program.account.incarraAgent.fetch(from.agent);
+      const destination = await program.account.incarraAgent.fetch(to.agent);
+      expect(source.reputationScore.toNumber()).to.equal(0);
+      expect(destination.reputationScore.toNumber()).to.equal(40);
+    });
+
+    it("rejects a transfer to an agent at the achievement cap", async () => {
+      const from = await createAgent();
+      const to = await createAgent();
+      await addAchievement(from.owner, from.agent, "Shared Paper", 30);
+      for (let i = 0; i < 20; i++) {
+        await addAchievement(to.owner, to.agent, `Award ${i}`, 1);
+      }
+
+      await expectError(transfer(from, to, 0), "TooManyAchievements");
+
+      const source = await program.account.incarraAgent.fetch(from.agent);
+      expect(source.achievements).to.have.length(1);
+      expect(source.reputationScore.toNumber()).to.equal(30);
+    });
+
+    it("requires the destination owner's signature", async () => {
+      const from = await createAgent();
+      const to = await createAgent();
+      await addAchievement(from.owner, from.agent, "Shared Paper", 30);
+
+      await expectError(transfer(from, to, 0, from.owner), "Unauthorized");
+
+      const destination = await program.account.incarraAgent.fetch(to.agent);
+      expect(destination.achievements).to.have.length(0);
+    });
+
+    it("re-derives reputation under the destination's multiplier", async () => {
+      await updateConfig({ verifiedAchievementMultiplierBps: new anchor.BN(15_000) });
+      try {
+        const from = await createAgent();
+        await verify(from.owner, from.agent);
+        await addAchievement(from.owner, from.agent, "Shared Paper", 30);
+        const to = await createAgent();
+
+        await transfer(from, to, 0);
+
+        const destination = await program.account.incarraAgent.fetch(to.agent);
+        expect(destination.achievements[0].reputation.toNumber()).to.equal(30);
+        expect(destination.reputationScore.toNumber()).to.equal(30);
+      } finally {
+        await updateConfig({ verifiedAchievementMultiplierBps: new anchor.BN(10_000) });
+      }
+    });
+  });
+
+  describe("rename_agent", () => {
+    before(() => updateConfig({ renameCooldownSeconds: new anchor.BN(2) }));
+    after(() => updateConfig({ renameCooldownSeconds: new anchor.BN(0) }));
+
+    const rename = (owner: anchor.web3.Keypair, agent: anchor.web3.PublicKey, name: string) =>
+      program.methods
+        .renameAgent(name)
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey, config: configPda })
+        .signers([owner])
+        .rpc();
+
+    it("renames the agent", async () => {
+      const { owner, agent } = await createAgent();
+
+      await rename(owner, agent, "ResearchBot");
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.agentName).to.equal("ResearchBot");
+    });
+
+    it("rejects a rename within the cooldown and allows one after it", async () => {
+      const { owner, agent } = await createAgent();
+      await rename(owner, agent, "First");
+
+      await expectError(rename(owner, agent, "Second"), "RenameCooldownActive");
+      await new Promise((resolve) => setTimeout(resolve, 3000));
+      await rename(owner, agent, "Third");
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.agentName).to.equal("Third");
+    });
+
+    it("rejects empty and over-length names", async () => {
+      const { owner, agent } = await createAgent();
+
+      await expectError(rename(owner, agent, ""), "AgentNameTooShort");
+      await expectError(rename(owner, agent, "n".repeat(51)), "AgentNameTooLong");
+    });
+  });
+
+  describe("get_claimable", () => {
+    const claimableOf = (agent: anchor.web3.PublicKey) =>
+      program.methods.getClaimable().accountsPartial({ incarraAgent: agent }).view();
+
+    it("reports level, achievement, and verification rewards not yet claimed", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent, 250);
+      await addAchievement(owner, agent, "First", 5);
+      await addAchievement(owner, agent, "Second", 5);
+      await verify(owner, agent);
+
+      const claimable = await claimableOf(agent);
+      expect(claimable.levelRewards.toNumber()).to.equal(2);
+      expect(claimable.achievementBonuses.toNumber()).to.equal(2);
+      expect(claimable.verificationBonus).to.equal(true);
+    });
+
+    it("reports nothing once everything is claimed", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent, 150);
+      await addAchievement(owner, agent, "First", 5);
+      await verify(owner, agent);
+
+      await program.methods
+        .claimRewards()
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+
+      const claimable = await claimableOf(agent);
+      expect(claimable.levelRewards.toNumber()).to.equal(0);
+      expect(claimable.achievementBonuses.toNumber()).to.equal(0);
+      expect(claimable.verificationBonus).to.equal(false);
+    });
+  });
+
+  describe("repeated verification", () => {
+    it("rejects a second verification without adding reputation", async () => {
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+      const before = (await program.account.incarraAgent.fetch(agent)).reputationScore;
+
+      await expectError(verify(owner, agent), "AlreadyVerified");
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.reputationScore.toNumber()).to.equal(before.toNumber());
+      expect(account.verificationBonusGranted.toNumber()).to.equal(50);
+    });
+  });
+
+  describe("explorer bonus", () => {
+    before(() => updateConfig({ explorerBonus: new anchor.BN(3) }));
+    after(() => updateConfig({ explorerBonus: new anchor.BN(0) }));
+
+    const interactConfirmed = (
+      owner: anchor.web3.Keypair,
+      agent: anchor.web3.PublicKey,
+      interactionType: any
+    ) =>
+      program.methods
+        .interactWithIncarra(interactionType, new anchor.BN(10), "{}", null)
+        .accountsPartial({ incarraAgent: agent, recorder: owner.publicKey, config: configPda })
+        .signers([owner])
+        .rpc({ commitment: "confirmed" });
+
+    it("grants the bonus on the first use of each type", async () => {
+      const { owner, agent } = await createAgent();
+
+      const first = await interactConfirmed(owner, agent, { conversation: {} });
+      const second = await interactConfirmed(owner, agent, { dataAnalysis: {} });
+
+      expect(hasEvent(await eventsOf(first), "ExplorerBonus")).to.equal(true);
+      expect(hasEvent(await eventsOf(second), "ExplorerBonus")).to.equal(true);
+      // Conversation 1 + DataAnalysis 5, plus two bonuses of 3
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.reputationScore.toNumber()).to.equal(12);
+    });
+
+    it("does not grant it again for a type already used", async () => {
+      const { owner, agent } = await createAgent();
+      await interactConfirmed(owner, agent, { conversation: {} });
+
+      const repeat = await interactConfirmed(owner, agent, { conversation: {} });
+
+      expect(hasEvent(await eventsOf(repeat), "ExplorerBonus")).to.equal(false);
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.reputationScore.toNumber()).to.equal(5);
+    });
+
+    it("still grants it for a type first used while flagged", async () => {
+      const { owner, agent } = await createAgent();
+      const setFlagged = (flagged: boolean) =>
+        program.methods
+          .setFlagged(flagged)
+          .accountsPartial({ incarraAgent: agent, config: configPda, admin })
+          .rpc();
+      await setFlagged(true);
+      await interactConfirmed(owner, agent, { conversation: {} });
+      await setFlagged(false);
+
+      const after = await interactConfirmed(owner, agent, { conversation: {} });
+
+      expect(hasEvent(await eventsOf(after), "ExplorerBonus")).to.equal(true);
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.reputationScore.toNumber()).to.equal(4);
+    });
+  });
+
+  describe("get_verification_ratio", () => {
+    const ratioAfter = async (verified: number, unverified: number) => {
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+      for (let i = 0; i < verified; i++) {
+        await addVerifiedCredential(owner, agent, Keypair.generate());
+      }
+      for (let i = 0; i < unverified; i++) {
+        await addCredential(owner, agent);
+      }
+
+      const ratio = await program.methods
+        .getVerificationRatio()
+        .accountsPartial({ incarraAgent: agent })
+        .view();
+      return ratio.toNumber();
+    };
+
+    it("is 10000 when every credential is verified", async () => {
+      expect(await ratioAfter(2, 0)).to.equal(10_000);
+    });
+
+    it("is 0 when no credential is verified", async () => {
+      expect(await ratioAfter(0, 2)).to.equal(0);
+    });
+
+    it("is 5000 when half are verified", async () => {
+      expect(await ratioAfter(1, 1)).to.equal(5_000);
+    });
+
+    it("is 0 without credentials", async () => {
+      expect(await ratioAfter(0, 0)).to.equal(0);
+    });
+  });
+
+  describe("admin_normalize", () => {
+    const normalize = (agent: anchor.web3.PublicKey) =>
+      program.methods
+        .adminNormalize()
+        .accountsPartial({ incarraAgent: agent, config: configPda, admin })
+        .rpc({ commitment: "confirmed" });
+
+    it("keeps a consistent agent as-is and emits NormalizedAgent", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent, 150, { dataAnalysis: {} });
+      await addAchievement(owner, agent, "Pioneer", 25);
+      const before = await program.account.incarraAgent.fetch(agent);
+
+      const sig = await normalize(agent);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.level.toNumber()).to.equal(2);
+      expect(account.reputationScore.toNumber()).to.equal(before.reputationScore.toNumber());
+      expect(hasEvent(await eventsOf(sig), "NormalizedAgent")).to.equal(true);
+    });
+
+    it("rejects non-admin signers", async () => {
+      const { agent } = await createAgent();
+      const outsider = Keypair.generate();
+
+      await expectError(
+        program.methods
+          .adminNormalize()
+          .accountsPartial({ incarraAgent: agent, config: configPda, admin: outsider.publicKey })
+          .signers([outsider])
+          .rpc(),
+        "Unauthorized"
+      );
+    });
+  });
+
+  describe("get_trust_score", () => {
+    const trustScore = async (agent: anchor.web3.PublicKey) =>
+      (
+        await program.methods
+          .getTrustScore()
+          .accountsPartial({ incarraAgent: agent, config: configPda })
+          .view()
+      ).toNumber();
+
+    it("gives a fresh unverified agent only the recency share", async () => {
+      const { agent } = await createAgent();
+
+      expect(await trustScore(agent)).to.equal(2_000);
+    });
+
+    it("rates a verified agent with verified credentials higher, within bounds", async () => {
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+      await addVerifiedCredential(owner, agent, Keypair.generate());
+
+      const score = await trustScore(agent);
+      expect(score).to.be.greaterThan(2_000);
+      expect(score).to.be.at.most(10_000);
+    });
+
+    it("drops the credential share as unverified credentials are added", async () => {
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+      await addVerifiedCredential(owner, agent, Keypair.generate());
+      const before = await trustScore(agent);
+
+      await addCredential(owner, agent);
+
+      expect(await trustScore(agent)).to.be.lessThan(before);
+    });
+  });
+
+  describe("resolve_carv_id", () => {
+    const SALT = Array.from({ length: 32 }, (_, i) => i);
+
+    const randomCarvId = () =>
+      "0x" + Keypair.generate().publicKey.toBuffer().toString("hex").slice(0, 40);
+
+    // Links a fresh Carv ID through commit/reveal so each test indexes its own
+    const linkCarvId = async (
+      owner: anchor.web3.Keypair,
+      agent: anchor.web3.PublicKey,
+      previous = CARV_ID
+    ) => {
+      const carvId = randomCarvId();
+      const hash = Array.from(
+        createHash("sha256")
+          .update(Buffer.concat([agent.toBuffer(), Buffer.from(carvId), Buffer.from(SALT)]))
+          .digest()
+      );
+      await program.methods
+        .commitCarvId(hash)
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+      await program.methods
+        .revealCarvId(carvId, SALT)
+        .accountsPartial({
+          incarraAgent: agent,
+          carvIndex: carvIndexPda(previous),
+          owner: owner.publicKey,
+        })
+        .signers([owner])
+        .rpc();
+      return carvId;
+    };
+
+    const indexCarvId = (
+      owner: anchor.web3.Keypair,
+      agent: anchor.web3.PublicKey,
+      carvId: string
+    ) =>
+      program.methods
+        .indexCarvId()
+        .accountsPartial({
+          incarraAgent: agent,
+          carvIndex: carvIndexPda(carvId),
+          owner: owner.publicKey,
+        })
+        .signers([owner])
+        .rpc();
+
+    const resolve = (carvId: string) =>
+      program.methods
+        .resolveCarvId(carvId)
+        .accountsPartial({ carvIndex: carvIndexPda(carvId) })
+        .view();
+
+    it("resolves an indexed Carv ID to its agent", async () => {
+      const { owner, agent } = await createAgent();
+      const carvId = await linkCarvId(owner, agent);
+      await verify(owner, agent);
+
+      await indexCarvId(owner, agent, carvId);
+
+      expect((await resolve(carvId)).toBase58()).to.equal(agent.toBase58());
+    });
+
+    it("fails with CarvIdNotFound for an unindexed Carv ID", async () => {
+      await expectViewError(resolve(randomCarvId()), "CarvIdNotFound");
+    });
+
+    it("only indexes verified Carv IDs", async () => {
+      const { owner, agent } = await createAgent();
+      const carvId = await linkCarvId(owner, agent);
+
+      await expectError(indexCarvId(owner, agent, carvId), "CarvIdNotVerified");
+    });
+
+    it("drops the index entry when the agent links a new Carv ID", async () => {
+      const { owner, agent } = await createAgent();
+      const oldId = await linkCarvId(owner, agent);
+      await verify(owner, agent);
+      await indexCarvId(owner, agent, oldId);
+
+      await linkCarvId(owner, agent, oldId);
+
+      await expectViewError(resolve(oldId), "CarvIdNotFound");
+    });
+  });
+
+  describe("context bonus", () => {
+    before(() => updateConfig({ contextBonusEnabled: true, maxContextBonus: new anchor.BN(2) }));
+    after(() =>
+      updateConfig({ contextBonusEnabled: false, maxContextBonus: new anchor.BN(3) })
+    );
+
+    const reputationFor = async (contextData: string) => {
+      const { owner, agent } = await createAgent();
+      await program.methods
+        .interactWithIncarra({ conversation: {} }, new anchor.BN(10), contextData, null)
+        .accountsPartial({ incarraAgent: agent, recorder: owner.publicKey, config: configPda })
+        .signers([owner])
+        .rpc();
+      const account = await program.account.incarraAgent.fetch(agent);
+      return account.reputationScore.toNumber();
+    };
+
+    it("adds nothing for an empty context", async () => {
+      expect(await reputationFor("")).to.equal(1);
+    });
+
+    it("adds a point per 100 bytes of context", async () => {
+      expect(await reputationFor("x".repeat(150))).to.equal(2);
+    });
+
+    it("caps the bonus at max_context_bonus", async () => {
+      expect(await reputationFor("x".repeat(500))).to.equal(3);
+    });
+  });
+
+  describe("get_streak", () => {
+    const streakOf = (agent: anchor.web3.PublicKey) =>
+      program.methods.getStreak().accountsPartial({ incarraAgent: agent }).view();
+
+    it("is zero before any interaction", async () => {
+      const { agent } = await createAgent();
+
+      const streak = await streakOf(agent);
+      expect(streak.currentDays).to.equal(0);
+      expect(streak.longestDays).to.equal(0);
+    });
+
+    it("counts same-day interactions as a single day", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent);
+      await interact(owner, agent);
+
+      const streak = await streakOf(agent);
+      expect(streak.currentDays).to.equal(1);
+      expect(streak.longestDays).to.equal(1);
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.currentStreakDays).to.equal(1);
+    });
+  });
+
+  describe("update_proficiencies", () => {
+    const updateProficiencies = (
+      owner: anchor.web3.Keypair,
+      agent: anchor.web3.PublicKey,
+      updates: [string, number][]
+    ) =>
+      program.methods
+        .updateProficiencies(updates.map(([area, proficiency]) => ({ area, proficiency })))
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey })
+        .signers([owner]);
+
+    it("applies known areas, skips unknown ones, and returns the count", async () => {
+      const { owner, agent } = await createAgent();
+      await addKnowledgeArea(owner, agent, "math");
+      await addKnowledgeArea(owner, agent, "rust");
+      const updates: [string, number][] = [
+        ["rust", 80],
+        ["cooking", 40],
+        ["math", 65],
+      ];
+
+      const sig = await updateProficiencies(owner, agent, updates).rpc({ commitment: "confirmed" });
+
+      const event = (await eventsOf(sig)).find((e) => hasEvent([e], "ProficienciesUpdated"));
+      expect(event.data.applied.toNumber()).to.equal(2);
+      const account = await program.account.incarraAgent.fetch(agent);
+      // Parallel to the sorted knowledge_areas: math, rust
+      expect(account.knowledgeAreaProficiency).to.deep.equal([65, 80]);
+    });
+
+    it("rejects a proficiency above 100", async () => {
+      const { owner, agent } = await createAgent();
+      await addKnowledgeArea(owner, agent, "math");
+
+      await expectError(
+        updateProficiencies(owner, agent, [["math", 101]]).rpc(),
+        "InvalidProficiency"
+      );
+    });
+  });
+
+  describe("get_credentials_commitment", () => {
+    const commitmentOf = (agent: anchor.web3.PublicKey) =>
+      program.methods.getCredentialsCommitment().accountsPartial({ incarraAgent: agent }).view();
+
+    it("is deterministic for a fixed credential set", async () => {
+      const { owner, agent } = await createAgent();
+      await addCredential(owner, agent);
+      await addCredential(owner, agent);
+
+      const first = await commitmentOf(agent);
+      const second = await commitmentOf(agent);
+
+      expect(first.count.toNumber()).to.equal(2);
+      expect(first.root).to.deep.equal(second.root);
+    });
+
+    it("changes when a credential is added", async () => {
+      const { owner, agent } = await createAgent();
+      const empty = await commitmentOf(agent);
+      await addCredential(owner, agent);
+
+      const after = await commitmentOf(agent);
+
+      expect(empty.root).to.deep.equal(new Array(32).fill(0));
+      expect(after.count.toNumber()).to.equal(1);
+      expect(after.root).to.not.deep.equal(empty.root);
+    });
+  });
+
+  describe("check_and_deactivate_if_dormant", () => {
+    after(() => updateConfig({ dormancyThresholdSeconds: new anchor.BN(0) }));
+
+    const crank = (agent: anchor.web3.PublicKey) =>
+      program.methods
+        .checkAndDeactivateIfDormant()
+        .accountsPartial({ incarraAgent: agent, config: configPda })
+        .rpc({ commitment: "confirmed" });
+
+    it("leaves a recently active agent active", async () => {
+      await updateConfig({ dormancyThresholdSeconds: new anchor.BN(3600) });
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent);
+
+      const sig = await crank(agent);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.isActive).to.equal(true);
+      expect(hasEvent(await eventsOf(sig), "AutoDeactivated")).to.equal(false);
+    });
+
+    it("deactivates an agent idle past the threshold", async () => {
+      await updateConfig({ dormancyThresholdSeconds: new anchor.BN(1) });
+      const { agent } = await createAgent();
+      await new Promise((resolve) => setTimeout(resolve, 3000));
+
+      const sig = await crank(agent);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.isActive).to.equal(false);
+      expect(hasEvent(await eventsOf(sig), "AutoDeactivated")).to.equal(true);
+    });
+
+    it("rejects interactions with a dormant agent until it reactivates", async () => {
+      await updateConfig({ dormancyThresholdSeconds: new anchor.BN(1) });
+      const { owner, agent } = await createAgent();
+      await new Promise((resolve) => setTimeout(resolve, 3000));
+      await crank(agent);
+
+      await expectError(interact(owner, agent), "AgentInactive");
+      await expectError(addKnowledgeArea(owner, agent, "math"), "AgentInactive");
+
+      await program.methods
+        .reactivateIncarra()
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+      await interact(owner, agent);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.isActive).to.equal(true);
+      expect(account.totalInteractions.toNumber()).to.equal(1);
+    });
+  });
+
+  describe("set_flagged", () => {
+    const setFlagged = (agent: anchor.web3.PublicKey, flagged: boolean) =>
+      program.methods
+        .setFlagged(flagged)
+        .accountsPartial({ incarraAgent: agent, config: configPda, admin })
+        .rpc();
+
+    it("earns no reputation from interactions while flagged", async () => {
+      const { owner, agent } = await createAgent();
+      await setFlagged(agent, true);
+
+      await interact(owner, agent, 10, { dataAnalysis: {} });
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.totalInteractions.toNumber()).to.equal(1);
+      expect(account.experience.toNumber()).to.equal(10);
+      expect(account.reputationScore.toNumber()).to.equal(0);
+    });
+
+    it("earns reputation again once the flag is cleared", async () => {
+      const { owner, agent } = await createAgent();
+      await setFlagged(agent, true);
+      await setFlagged(agent, false);
+
+      await interact(owner, agent, 10, { dataAnalysis: {} });
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.reputationScore.toNumber()).to.equal(5);
+    });
+
+    it("rejects a flag set by a non-admin", async () => {
+      const { owner, agent } = await createAgent();
+
+      await expectError(
+        program.methods
+          .setFlagged(true)
+          .accountsPartial({ incarraAgent: agent, config: configPda, admin: owner.publicKey })
+          .signers([owner])
+          .rpc(),
+        "Unauthorized"
+      );
+    });
+  });
+
+  describe("get_score_change_since_checkpoint", () => {
+    const checkpoint = (owner: anchor.web3.Keypair, agent: anchor.web3.PublicKey) =>
+      program.methods
+        .checkpointReputation()
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+
+    const changeOf = async (agent: anchor.web3.PublicKey) =>
+      (
+        await program.methods
+          .getScoreChangeSinceCheckpoint()
+          .accountsPartial({ incarraAgent: agent })
+          .view()
+      ).toNumber();
+
+    it("is positive after reputation is gained", async () => {
+      const { owner, agent } = await createAgent();
+      await checkpoint(owner, agent);
+
+      await interact(owner, agent, 10, { dataAnalysis: {} });
+
+      expect(await changeOf(agent)).to.equal(5);
+    });
+
+    it("is negative after a penalty", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent, 10, { dataAnalysis: {} });
+      await checkpoint(owner, agent);
+
+      await program.methods
+        .penalizeAgent(new anchor.BN(3), "spam")
+        .accountsPartial({ incarraAgent: agent, config: configPda, admin })
+        .rpc();
+
+      expect(await changeOf(agent)).to.equal(-3);
+    });
+
+    it("is zero when nothing changed", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent, 10, { dataAnalysis: {} });
+      await checkpoint(owner, agent);
+
+      expect(await changeOf(agent)).to.equal(0);
+    });
+  });
+
+  describe("set_handle", () => {
+    const handleRegistryPda = (handle: string) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("handle"), Buffer.from(handle)],
+        program.programId
+      )[0];
+
+    const setHandle = (
+      owner: anchor.web3.Keypair,
+      agent: anchor.web3.PublicKey,
+      handle: string,
+      register = false,
+      previous: string | null = null
+    ) =>
+      program.methods
+        .setHandle(handle)
+        .accountsPartial({
+          incarraAgent: agent,
+          owner: owner.publicKey,
+          config: configPda,
+          handleRegistry: register ? handleRegistryPda(handle) : null,
+          previousRegistry: previous === null ? null : handleRegistryPda(previous),
+        })
+        .signers([owner])
+        .rpc();
+
+    const randomHandle = () => "h" + Math.random().toString(36).slice(2, 12);
+
+    it("sets a valid handle", async () => {
+      const { owner, agent } = await createAgent();
+
+      await setHandle(owner, agent, "incarra42");
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.handle).to.equal("incarra42");
+    });
+
+    it("rejects uppercase and other invalid characters", async () => {
+      const { owner, agent } = await createAgent();
+
+      await expectError(setHandle(owner, agent, "Incarra"), "InvalidHandle");
+      await expectError(setHandle(owner, agent, "in-carra"), "InvalidHandle");
+      await expectError(setHandle(owner, agent, "a".repeat(21)), "InvalidHandle");
+    });
+
+    describe("with unique handles", () => {
+      before(() => updateConfig({ uniqueHandles: true }));
+      after(() => updateConfig({ uniqueHandles: false }));
+
+      it("requires the handle registry account", async () => {
+        const { owner, agent } = await createAgent();
+
+        await expectError(setHandle(owner, agent, randomHandle()), "HandleRegistryRequired");
+      });
+
+      it("rejects a handle another agent already claimed", async () => {
+        const handle = randomHandle();
+        const first = await createAgent();
+        const second = await createAgent();
+        await setHandle(first.owner, first.agent, handle, true);
+
+        await expectError(setHandle(second.owner, second.agent, handle, true), "HandleTaken");
+
+        const account = await program.account.incarraAgent.fetch(second.agent);
+        expect(account.handle).to.equal("");
+      });
+
+      it("lets an agent set its own handle again", async () => {
+        const handle = randomHandle();
+        const { owner, agent } = await createAgent();
+        await setHandle(owner, agent, handle, true);
+
+        await setHandle(owner, agent, handle, true, handle);
+
+        const account = await program.account.incarraAgent.fetch(agent);
+        expect(account.handle).to.equal(handle);
+      });
+
+      it("frees the previous handle when an agent changes it", async () => {
+        const [oldHandle, newHandle] = [randomHandle(), randomHandle()];
+        const first = await createAgent();
+        const second = await createAgent();
+        await setHandle(first.owner, first.agent, oldHandle, true);
+
+        await setHandle(first.owner, first.agent, newHandle, true, oldHandle);
+        await setHandle(second.owner, second.agent, oldHandle, true);
+
+        const account = await program.account.incarraAgent.fetch(second.agent);
+        expect(account.handle).to.equal(oldHandle);
+      });
+
+      it("frees the handle when its agent closes", async () => {
+        const handle = randomHandle();
+        const first = await createAgent();
+        const second = await createAgent();
+        await setHandle(first.owner, first.agent, handle, true);
+
+        await program.methods
+          .closeIncarra()
+          .accountsPartial({
+            incarraAgent: first.agent,
+            owner: first.owner.publicKey,
+            handleRegistry: handleRegistryPda(handle),
+            carvIndex: carvIndexPda(CARV_ID),
+          })
+          .signers([first.owner])
+          .rpc();
+        await setHandle(second.owner, second.agent, handle, true);
+
+        const registry = await program.account.handleRegistry.fetch(handleRegistryPda(handle));
+        expect(registry.agent.toBase58()).to.equal(second.agent.toBase58());
+      });
+
+      it("lets an agent claim a handle set before unique handles", async () => {
+        const handle = randomHandle();
+        const { owner, agent } = await createAgent();
+        await updateConfig({ uniqueHandles: false });
+        await setHandle(owner, agent, handle);
+        await updateConfig({ uniqueHandles: true });
+
+        await setHandle(owner, agent, handle, true, handle);
+
+        const registry = await program.account.handleRegistry.fetch(handleRegistryPda(handle));
+        expect(registry.agent.toBase58()).to.equal(agent.toBase58());
+      });
+    });
+  });
+
+  describe("achievement expiry", () => {
+    const nowSeconds = async () => {
+      const slot = await provider.connection.getSlot();
+      return await provider.connection.getBlockTime(slot);
+    };
+
+    const activeNames = async (agent: anchor.web3.PublicKey) =>
+      (
+        await program.methods
+          .getActiveAchievements()
+          .accountsPartial({ incarraAgent: agent })
+          .view()
+      ).map((a) => a.name);
+
+    it("drops an achievement from the active list once it expires", async () => {
+      const { owner, agent } = await createAgent();
+      await addAchievement(owner, agent, "Permanent", 10);
+      await addAchievement(owner, agent, "Hackathon", 25, (await nowSeconds()) + 2);
+
+      expect(await activeNames(agent)).to.deep.equal(["Permanent", "Hackathon"]);
+      await new Promise((resolve) => setTimeout(resolve, 4000));
+
+      expect(await activeNames(agent)).to.deep.equal(["Permanent"]);
+    });
+
+    const breakdownOf = (agent: anchor.web3.PublicKey) =>
+      program.methods.getReputationBreakdown().accountsPartial({ incarraAgent: agent }).view();
+
+    const breakdownTotal = (breakdown: any) =>
+      breakdown.fromInteractions.toNumber() +
+      breakdown.fromDataSources.toNumber() +
+      breakdown.fromResearch.toNumber() +
+      breakdown.fromReferrals.toNumber() +
+      breakdown.fromCredentials.toNumber() +
+      breakdown.fromAchievements.toNumber() +
+      breakdown.fromKnowledgeAreas.toNumber() +
+      breakdown.fromVerification.toNumber() -
+      breakdown.lostToDecay.toNumber() -
+      breakdown.lostToPenalties.toNumber();
+
+    it("settles expired achievements when the reputation is checkpointed", async () => {
+      const { owner, agent } = await createAgent();
+      await addAchievement(owner, agent, "Permanent", 10);
+      await addAchievement(owner, agent, "Hackathon", 25, (await nowSeconds()) + 2);
+      await new Promise((resolve) => setTimeout(resolve, 4000));
+
+      // Until settled, the breakdown still matches the stored score
+      let account = await program.account.incarraAgent.fetch(agent);
+      expect(account.reputationScore.toNumber()).to.equal(35);
+      expect(breakdownTotal(await breakdownOf(agent))).to.equal(35);
+
+      await program.methods
+        .checkpointReputation()
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+
+      account = await program.account.incarraAgent.fetch(agent);
+      const breakdown = await breakdownOf(agent);
+      expect(breakdown.fromAchievements.toNumber()).to.equal(10);
+      expect(account.reputationScore.toNumber()).to.equal(10);
+      expect(account.lastCheckpointScore.toNumber()).to.equal(10);
+      expect(breakdownTotal(breakdown)).to.equal(10);
+    });
+
+    it("rejects an expiry that has already passed", async () => {
+      const { owner, agent } = await createAgent();
+
+      await expectError(
+        addAchievement(owner, agent, "Late", 5, (await nowSeconds()) - 10),
+        "AchievementAlreadyExpired"
+      );
+    });
+  });
+
+  describe("get_seniority", () => {
+    const seniorityOf = async (agent: anchor.web3.PublicKey) =>
+      (
+        await program.methods.getSeniority().accountsPartial({ incarraAgent: agent }).view()
+      ).toNumber();
+
+    it("returns the creation time, ordering agents created in sequence", async () => {
+      const first = await createAgent();
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+      const second = await createAgent();
+
+      const account = await program.account.incarraAgent.fetch(first.agent);
+      expect(await seniorityOf(first.agent)).to.equal(account.createdAt.toNumber());
+      expect(await seniorityOf(second.agent)).to.be.greaterThan(await seniorityOf(first.agent));
+    });
+  });
+
+  describe("create_incarra_agent_with_referrer", () => {
+    const createReferred = async (referrer: anchor.web3.PublicKey) => {
+      const owner = Keypair.generate();
+      await airdrop(owner.publicKey);
+      const agent = agentPda(owner.publicKey);
+
+      await program.methods
+        .createIncarraAgentWithReferrer("Referred", "Helpful AI assistant", CARV_ID, "0x", referrer)
+        .accountsPartial({
+          incarraAgent: agent,
+          referrerAgent: referrer,
+          user: owner.publicKey,
+          config: configPda,
+        })
+        .signers([owner])
+        .rpc();
+
+      return { owner, agent };
+    };
+
+    it("credits the referrer with reputation and a referral", async () => {
+      const referrer = await createAgent();
+
+      const { agent } = await createReferred(referrer.agent);
+      await createReferred(referrer.agent);
+
+      const account = await program.account.incarraAgent.fetch(referrer.agent);
+      expect(account.referralsCount.toNumber()).to.equal(2);
+      expect(account.referralReputation.toNumber()).to.equal(20);
+      expect(account.reputationScore.toNumber()).to.equal(20);
+      const referred = await program.account.incarraAgent.fetch(agent);
+      expect(referred.agentName).to.equal("Referred");
+      expect(referred.reputationScore.toNumber()).to.equal(0);
+    });
+
+    it("rejects the new agent referring itself", async () => {
+      const owner = Keypair.generate();
+      await airdrop(owner.publicKey);
+      const agent = agentPda(owner.publicKey);
+
+      // The agent's own PDA doesn't exist yet, so it can't be loaded as the referrer
+      await expectError(
+        program.methods
+          .createIncarraAgentWithReferrer("Self", "Helpful AI assistant", CARV_ID, "0x", agent)
+          .accountsPartial({
+            incarraAgent: agent,
+            referrerAgent: agent,
+            user: owner.publicKey,
+            config: configPda,
+          })
+          .signers([owner])
+          .rpc(),
+        "AccountNotInitialized"
+      );
+    });
+  });
+
+  describe("get_verification_expiry", () => {
+    after(() => updateConfig({ verificationValiditySeconds: new anchor.BN(0) }));
+
+    const expiryOf = (agent: anchor.web3.PublicKey) =>
+      program.methods
+        .getVerificationExpiry()
+        .accountsPartial({ incarraAgent: agent, config: configPda })
+        .view();
+
+    it("is verified_at plus the validity window for a time-limited verification", async () => {
+      await updateConfig({ verificationValiditySeconds: new anchor.BN(3600) });
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      const expiry = await expiryOf(agent);
+      expect(expiry.toNumber()).to.equal(account.verifiedAt.toNumber() + 3600);
+    });
+
+    it("is None for a permanent verification", async () => {
+      await updateConfig({ verificationValiditySeconds: new anchor.BN(0) });
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+
+      expect(await expiryOf(agent)).to.equal(null);
+    });
+
+    it("is None for an unverified agent", async () => {
+      await updateConfig({ verificationValiditySeconds: new anchor.BN(3600) });
+      const { agent } = await createAgent();
+
+      expect(await expiryOf(agent)).to.equal(null);
+    });
+  });
+
+  describe("set_interactions_paused", () => {
+    const setPaused = (
+      owner: anchor.web3.Keypair,
+      agent: anchor.web3.PublicKey,
+      paused: boolean
+    ) =>
+      program.methods
+        .setInteractionsPaused(paused)
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+
+    it("blocks interactions while paused", async () => {
+      const { owner, agent } = await createAgent();
+      await setPaused(owner, agent, true);
+
+      await expectError(interact(owner, agent), "InteractionsPaused");
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.totalInteractions.toNumber()).to.equal(0);
+    });
+
+    it("still allows profile edits while paused", async () => {
+      const { owner, agent } = await createAgent();
+      await setPaused(owner, agent, true);
+
+      await program.methods
+        .setPersonalityVersioned("focused")
+        .accountsPartial({ incarraAgent: agent, owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+      await addKnowledgeArea(owner, agent, "math");
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.personality).to.equal("focused");
+      expect(account.knowledgeAreas).to.deep.equal(["math"]);
+    });
+
+    it("allows interactions again once resumed", async () => {
+      const { owner, agent } = await createAgent();
+      await setPaused(owner, agent, true);
+      await setPaused(owner, agent, false);
+
+      await interact(owner, agent);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.totalInteractions.toNumber()).to.equal(1);
+    });
+  });
+
+  describe("cosign_credential", () => {
+    const cosign = (
+      target: anchor.web3.PublicKey,
+      cosigner: { owner: anchor.web3.Keypair; agent: anchor.web3.PublicKey }
+    ) =>
+      program.methods
+        .cosignCredential(new anchor.BN(0))
+        .accountsPartial({
+          targetAgent: target,
+          cosignerAgent: cosigner.agent,
+          owner: cosigner.owner.publicKey,
+          config: configPda,
+        })
+        .signers([cosigner.owner])
+        .rpc();
+
+    it("verifies the credential once the threshold is reached", async () => {
+      const target = await createAgent();
+      await verify(target.owner, target.agent);
+      await addCredential(target.owner, target.agent);
+      const before = await program.account.incarraAgent.fetch(target.agent);
+
+      await cosign(target.agent, await createAgent());
+      let account = await program.account.incarraAgent.fetch(target.agent);
+      expect(account.credentials[0].isVerified).to.equal(false);
+
+      await cosign(target.agent, await createAgent());
+      account = await program.account.incarraAgent.fetch(target.agent);
+      expect(account.credentials[0].cosigners.length).to.equal(2);
+      expect(account.credentials[0].isVerified).to.equal(true);
+      expect(account.reputationScore.toNumber()).to.equal(
+        before.reputationScore.toNumber() + 5
+      );
+    });
+
+    it("rejects a second co-sign from the same agent", async () => {
+      const target = await createAgent();
+      await verify(target.owner, target.agent);
+      await addCredential(target.owner, target.agent);
+      const cosigner = await createAgent();
+      await cosign(target.agent, cosigner);
+
+      await expectError(cosign(target.agent, cosigner), "AlreadyCosigned");
+
+      const account = await program.account.incarraAgent.fetch(target.agent);
+      expect(account.credentials[0].cosigners.length).to.equal(1);
+      expect(account.credentials[0].isVerified).to.equal(false);
+    });
+
+    it("rejects a co-sign from a retired agent", async () => {
+      const target = await createAgent();
+      await verify(target.owner, target.agent);
+      await addCredential(target.owner, target.agent);
+      const cosigner = await createAgent();
+      await program.methods
+        .retireIncarra()
+        .accountsPartial({ incarraAgent: cosigner.agent, owner: cosigner.owner.publicKey })
+        .signers([cosigner.owner])
+        .rpc();
+
+      await expectError(cosign(target.agent, cosigner), "AgentRetired");
+
+      const account = await program.account.incarraAgent.fetch(target.agent);
+      expect(account.credentials[0].cosigners.length).to.equal(0);
+    });
+  });
+
+  describe("get_interaction_velocity", () => {
+    const DAY = 86_400;
+    const velocityOf = (agent: anchor.web3.PublicKey, now: number) =>
+      program.methods
+        .getInteractionVelocity(new anchor.BN(now))
+        .accountsPartial({ incarraAgent: agent })
+        .view()
+        .then((velocity: anchor.BN) => velocity.toNumber());
+
+    it("averages interactions over the days since creation", async () => {
+      const { owner, agent } = await createAgent();
+      for (let i = 0; i < 4; i++) {
+        await interact(owner, agent);
+      }
+      const account = await program.account.incarraAgent.fetch(agent);
+      const createdAt = account.createdAt.toNumber();
+
+      expect(await velocityOf(agent, createdAt + DAY)).to.equal(4);
+      expect(await velocityOf(agent, createdAt + 2 * DAY)).to.equal(2);
+    });
+
+    it("counts a just-created agent as one day old", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent);
+      await interact(owner, agent);
+      const account = await program.account.incarraAgent.fetch(agent);
+
+      expect(await velocityOf(agent, account.createdAt.toNumber())).to.equal(2);
+    });
+  });
+
+  describe("creation stake", () => {
+    const STAKE = LAMPORTS_PER_SOL;
+
+    before(() => updateConfig({ minCreationStake: new anchor.BN(STAKE) }));
+    after(() => updateConfig({ minCreationStake: new anchor.BN(0) }));
+
+    it("rejects a creator who cannot cover the stake", async () => {
+      await updateConfig({ minCreationStake: new anchor.BN(5 * STAKE) });
+      try {
+        await expectError(createAgent(), "InsufficientStake");
+      } finally {
+        await updateConfig({ minCreationStake: new anchor.BN(STAKE) });
+      }
+    });
+
+    it("locks the stake in the agent account", async () => {
+      const { agent } = await createAgent();
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.stakeLamports.toNumber()).to.equal(STAKE);
+      const info = await provider.connection.getAccountInfo(agent);
+      const rent = await provider.connection.getMinimumBalanceForRentExemption(
+        info.data.length
+      );
+      expect(info.lamports).to.equal(rent + STAKE);
+    });
+
+    it("returns the stake and rent to the owner on close", async () => {
+      const { owner, agent } = await createAgent();
+      const locked = await provider.connection.getBalance(agent);
+      const before = await provider.connection.getBalance(owner.publicKey);
+
+      await program.methods
+        .closeIncarra()
+        .accountsPartial({
+          incarraAgent: agent,
+          owner: owner.publicKey,
+          handleRegistry: null,
+          carvIndex: null,
+        })
+        .signers([owner])
+        .rpc();
+
+      const after = await provider.connection.getBalance(owner.publicKey);
+      expect(after).to.be.closeTo(before + locked, 10_000);
+      expect(await provider.connection.getAccountInfo(agent)).to.equal(null);
+    });
+  });
+
+  describe("get_level_up", () => {
+    const levelUpOf = (agent: anchor.web3.PublicKey, level: number) =>
+      program.methods
+        .getLevelUp(new anchor.BN(level))
+        .accountsPartial({ incarraAgent: agent })
+        .view();
+
+    it("returns the recorded level-up for a level in history", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent, 150);
+
+      const record = await levelUpOf(agent, 2);
+      expect(record.level.toNumber()).to.equal(2);
+      expect(record.totalExperience.toNumber()).to.equal(150);
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(record.timestamp.toNumber()).to.equal(account.lastInteraction.toNumber());
+    });
+
+    it("records every level crossed by a multi-level jump", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent, 350);
+
+      for (const level of [2, 3, 4]) {
+        const record = await levelUpOf(agent, level);
+        expect(record.level.toNumber()).to.equal(level);
+        expect(record.totalExperience.toNumber()).to.equal(350);
+      }
+      expect(await levelUpOf(agent, 5)).to.equal(null);
+    });
+
+    it("returns null for a level not in history", async () => {
+      const { owner, agent } = await createAgent();
+      await interact(owner, agent, 150);
+
+      expect(await levelUpOf(agent, 3)).to.equal(null);
+    });
+  });
+
+  describe("import_credentials", () => {
+    const MAX_CREDENTIALS = 10;
+
+    const importCredentials = (
+      owner: anchor.web3.Keypair,
+      agent: anchor.web3.PublicKey,
+      count: number
+    ) =>
+      program.methods
+        .importCredentials(
+          Array.from({ length: count }, (_, i) => ({
+            credentialType: "Skill",
+            credentialData: `{"n":${i}}`,
+            issuer: "Registry",
+          }))
+        )
+        .accountsPartial({
+          incarraAgent: agent,
+          owner: owner.publicKey,
+          config: configPda,
+        })
+        .signers([owner])
+        .rpc();
+
+    after(() => updateConfig({ verifiedImportGrace: false }));
+
+    it("lets a verified agent exceed the standard cap with the grace on", async () => {
+      await updateConfig({ verifiedImportGrace: true });
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+
+      await importCredentials(owner, agent, MAX_CREDENTIALS + 2);
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.credentials.length).to.equal(MAX_CREDENTIALS + 2);
+      const size = await program.methods
+        .getAccountSize()
+        .accountsPartial({ incarraAgent: agent })
+        .view();
+      const info = await provider.connection.getAccountInfo(agent);
+      expect(size.toNumber()).to.equal(info.data.length);
+      expect(size.toNumber()).to.be.greaterThan(10 * 1024);
+    });
+
+    it("holds a verified agent to the standard cap with the grace off", async () => {
+      await updateConfig({ verifiedImportGrace: false });
+      const { owner, agent } = await createAgent();
+      await verify(owner, agent);
+
+      await expectError(
+        importCredentials(owner, agent, MAX_CREDENTIALS + 2),
+        "TooManyCredentials"
+      );
+
+      const account = await program.account.incarraAgent.fetch(agent);
+      expect(account.credentials.length).to.equal(0);
+    });
   });
 });